@@ -0,0 +1,577 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{BufRead, Write};
+use std::sync::OnceLock;
+
+use anyhow::{bail, Context, Result};
+
+/// The digits spelled out as words, each at the index of the digit it stands for
+const DIGIT_WORDS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+/// Which ways of writing a digit count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vocabulary {
+    /// Only ASCII digits, as in part a
+    Digits,
+    /// ASCII digits and the lowercase words from "one" to "nine", as in part b
+    Words,
+    /// ASCII digits and the words from "zero" to "nine", in any mix of cases
+    ExtendedWords,
+}
+
+impl Vocabulary {
+    /// Each word that counts, with the digit it stands for
+    fn words(self) -> impl Iterator<Item = (u32, &'static str)> {
+        (0..)
+            .zip(DIGIT_WORDS)
+            .filter(move |&(digit, _)| match self {
+                Vocabulary::Digits => false,
+                Vocabulary::Words => digit != 0,
+                Vocabulary::ExtendedWords => true,
+            })
+    }
+}
+
+/// An Aho-Corasick automaton for the spelled-out digits,
+/// so that a line can be searched for all nine of them in a single pass
+struct Matcher {
+    /// For each state, the state to move to on each lowercase letter
+    transitions: Vec<[usize; 26]>,
+    /// The digit whose word has just been completed on reaching each state, if any
+    outputs: Vec<Option<u32>>,
+    /// Whether uppercase letters in a line match the (lowercase) words
+    case_insensitive: bool,
+}
+
+impl Matcher {
+    fn new(words: impl IntoIterator<Item = (u32, Vec<u8>)>, case_insensitive: bool) -> Self {
+        // Build a trie of the words. State 0 is the root, which is never anyone's child,
+        // so 0 doubles as "no child" until the failure transitions are filled in.
+        let mut transitions = vec![[0; 26]];
+        let mut outputs = vec![None];
+        for (digit, word) in words {
+            let mut state = 0;
+            for letter in word {
+                let letter = (letter - b'a') as usize;
+                if transitions[state][letter] == 0 {
+                    transitions.push([0; 26]);
+                    outputs.push(None);
+                    transitions[state][letter] = transitions.len() - 1;
+                }
+                state = transitions[state][letter];
+            }
+            outputs[state] = Some(digit);
+        }
+
+        // Breadth-first, point every missing transition at wherever the longest suffix
+        // that's still a prefix of some word would go, so that scanning never backtracks
+        let mut fallback = vec![0; transitions.len()];
+        let mut queue = VecDeque::from_iter(transitions[0].iter().copied().filter(|&s| s != 0));
+        while let Some(state) = queue.pop_front() {
+            outputs[state] = outputs[state].or(outputs[fallback[state]]);
+            // The fallback state is shallower, so its transitions are already complete
+            let fallback_transitions = transitions[fallback[state]];
+            for (child, &fallback_child) in transitions[state].iter_mut().zip(&fallback_transitions)
+            {
+                if *child == 0 {
+                    *child = fallback_child;
+                } else {
+                    fallback[*child] = fallback_child;
+                    queue.push_back(*child);
+                }
+            }
+        }
+        Matcher {
+            transitions,
+            outputs,
+            case_insensitive,
+        }
+    }
+
+    /// A matcher for the words read forwards
+    fn forwards(vocabulary: Vocabulary) -> Self {
+        let words = vocabulary
+            .words()
+            .map(|(digit, word)| (digit, word.bytes().collect()));
+        Matcher::new(words, vocabulary == Vocabulary::ExtendedWords)
+    }
+
+    /// A matcher for the words read backwards, for finding the last digit in a line
+    fn backwards(vocabulary: Vocabulary) -> Self {
+        let words = vocabulary
+            .words()
+            .map(|(digit, word)| (digit, word.bytes().rev().collect()));
+        Matcher::new(words, vocabulary == Vocabulary::ExtendedWords)
+    }
+
+    /// The first digit in `chars`, whether it's written as a digit or spelled out,
+    /// along with the index of the character that completed it.
+    ///
+    /// None of the words contains another, so the first word to be completed
+    /// is also the first to have started, even when words overlap
+    fn first_digit(&self, chars: impl Iterator<Item = (usize, char)>) -> Option<(usize, u32)> {
+        let mut state = 0;
+        for (i, c) in chars {
+            if let Some(digit) = c.to_digit(10) {
+                return Some((i, digit));
+            }
+            let c = if self.case_insensitive {
+                c.to_ascii_lowercase()
+            } else {
+                c
+            };
+            state = if c.is_ascii_lowercase() {
+                self.transitions[state][(c as u8 - b'a') as usize]
+            } else {
+                0
+            };
+            if let Some(digit) = self.outputs[state] {
+                return Some((i, digit));
+            }
+        }
+        None
+    }
+}
+
+/// The matchers for the first and last digits, built the first time they're needed
+fn matchers(vocabulary: Vocabulary) -> &'static (Matcher, Matcher) {
+    static MATCHERS: [OnceLock<(Matcher, Matcher)>; 3] = [const { OnceLock::new() }; 3];
+    MATCHERS[vocabulary as usize].get_or_init(|| {
+        (
+            Matcher::forwards(vocabulary),
+            Matcher::backwards(vocabulary),
+        )
+    })
+}
+
+/// Where a digit was found in a line, and how it was written there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DigitMatch<'a> {
+    /// The digit or word as it appears in the line
+    pub token: &'a str,
+    /// The byte index in the line where the token starts
+    pub index: usize,
+    pub value: u32,
+}
+
+impl fmt::Display for DigitMatch<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at col {}", self.token, self.index + 1)
+    }
+}
+
+/// How many bytes the token for `value` found at index `i` of `line` takes up.
+/// The words are all ASCII, so each one takes up a byte per letter.
+fn token_length(line: &str, i: usize, value: u32) -> usize {
+    if line.as_bytes()[i].is_ascii_digit() {
+        1
+    } else {
+        DIGIT_WORDS[value as usize].len()
+    }
+}
+
+/// The first digit in `line`, if there are any
+pub fn find_first_digit(line: &str, vocabulary: Vocabulary) -> Option<DigitMatch<'_>> {
+    let (forwards, _) = matchers(vocabulary);
+    // Reading forwards, a word is completed by its last letter
+    let (end, value) = forwards.first_digit(line.char_indices())?;
+    let start = end + 1 - token_length(line, end, value);
+    Some(DigitMatch {
+        token: &line[start..=end],
+        index: start,
+        value,
+    })
+}
+
+/// The last digit in `line`, if there are any
+pub fn find_last_digit(line: &str, vocabulary: Vocabulary) -> Option<DigitMatch<'_>> {
+    let (_, backwards) = matchers(vocabulary);
+    // Reading backwards, a word is completed by its first letter
+    let (start, value) = backwards.first_digit(line.char_indices().rev())?;
+    let end = start + token_length(line, start, value);
+    Some(DigitMatch {
+        token: &line[start..end],
+        index: start,
+        value,
+    })
+}
+
+/// The first and last digits in `line`, which are the same match if there's only one.
+/// Returns `None` if there are no digits at all.
+pub fn find_digits(line: &str, vocabulary: Vocabulary) -> Option<(DigitMatch<'_>, DigitMatch<'_>)> {
+    let first = find_first_digit(line, vocabulary)?;
+    let last = find_last_digit(line, vocabulary)?;
+    Some((first, last))
+}
+
+/// The first digit in `line` followed by the last one, as a two-digit number
+pub fn calibration_value(line: &str, vocabulary: Vocabulary) -> Result<u32> {
+    let Some((first, last)) = find_digits(line, vocabulary) else {
+        bail!("Couldn't find a digit in {line:?}")
+    };
+    Ok((first.value * 10) + last.value)
+}
+
+/// Each line's calibration value, in order. A line without any digits gives an error
+/// saying which line it was, but doesn't stop the lines after it from being read.
+pub fn calibration_values(
+    input: &str,
+    vocabulary: Vocabulary,
+) -> impl Iterator<Item = Result<u32>> + '_ {
+    read_calibration_values(input.as_bytes(), vocabulary)
+}
+
+/// Like [`calibration_values`], but reading the lines one at a time from `reader`
+/// rather than needing them all in memory at once
+pub fn read_calibration_values(
+    reader: impl BufRead,
+    vocabulary: Vocabulary,
+) -> impl Iterator<Item = Result<u32>> {
+    reader.lines().enumerate().map(move |(i, line)| {
+        let line = line.with_context(|| format!("Failed to read line {}", i + 1))?;
+        calibration_value(&line, vocabulary)
+            .with_context(|| format!("line {} ('{line}') contains no digits", i + 1))
+    })
+}
+
+/// The sum of all the calibration values in `reader`
+pub fn solve(reader: impl BufRead, vocabulary: Vocabulary) -> Result<u32> {
+    read_calibration_values(reader, vocabulary).sum()
+}
+
+/// Like [`solve`], but also writing each line to `out` along with
+/// where its first and last digits were found, to help track down misread lines
+pub fn solve_explained(
+    reader: impl BufRead,
+    vocabulary: Vocabulary,
+    mut out: impl Write,
+) -> Result<u32> {
+    let mut total = 0;
+    for (i, line) in reader.lines().enumerate() {
+        let line = line.with_context(|| format!("Failed to read line {}", i + 1))?;
+        let (first, last) = find_digits(&line, vocabulary)
+            .with_context(|| format!("line {} ('{line}') contains no digits", i + 1))?;
+        let value = (first.value * 10) + last.value;
+        writeln!(out, "{line}: first {first}, last {last} => {value}")?;
+        total += value;
+    }
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, Read};
+
+    use crate::{
+        calibration_value, calibration_values, find_digits, find_first_digit, find_last_digit,
+        solve, solve_explained, DigitMatch, Vocabulary,
+    };
+
+    #[test]
+    fn test_part_a_example() {
+        let example = [
+            ("1abc2", 12),
+            ("pqr3stu8vwx", 38),
+            ("a1b2c3d4e5f", 15),
+            ("treb7uchet", 77),
+        ];
+        for (line, value) in example {
+            assert_eq!(
+                calibration_value(line, Vocabulary::Digits).unwrap(),
+                value,
+                "{line}"
+            )
+        }
+        let input = Vec::from_iter(example.iter().map(|(line, _)| *line)).join("\n");
+        let values =
+            Result::<Vec<_>, _>::from_iter(calibration_values(&input, Vocabulary::Digits)).unwrap();
+        assert_eq!(values, [12, 38, 15, 77]);
+        assert_eq!(solve(input.as_bytes(), Vocabulary::Digits).unwrap(), 142)
+    }
+
+    #[test]
+    fn test_part_b_example() {
+        let example = [
+            ("two1nine", 29),
+            ("eightwothree", 83),
+            ("abcone2threexyz", 13),
+            ("xtwone3four", 24),
+            ("4nineeightseven2", 42),
+            ("zoneight234", 14),
+            ("7pqrstsixteen", 76),
+        ];
+        for (line, value) in example {
+            assert_eq!(
+                calibration_value(line, Vocabulary::Words).unwrap(),
+                value,
+                "{line}"
+            )
+        }
+        let input = Vec::from_iter(example.iter().map(|(line, _)| *line)).join("\n");
+        let values =
+            Result::<Vec<_>, _>::from_iter(calibration_values(&input, Vocabulary::Words)).unwrap();
+        assert_eq!(values, [29, 83, 13, 24, 42, 14, 76]);
+        assert_eq!(solve(input.as_bytes(), Vocabulary::Words).unwrap(), 281)
+    }
+
+    #[test]
+    fn test_words_only_count_for_part_b() {
+        assert_eq!(
+            calibration_value("two1nine", Vocabulary::Digits).unwrap(),
+            11
+        );
+        assert_eq!(
+            calibration_value("7pqrstsixteen", Vocabulary::Digits).unwrap(),
+            77
+        );
+        assert!(calibration_value("eightwothree", Vocabulary::Digits).is_err());
+        assert_eq!(
+            calibration_value("eightwothree", Vocabulary::Words).unwrap(),
+            83
+        );
+    }
+
+    #[test]
+    fn test_extended_words() {
+        let value = |line, vocabulary| calibration_value(line, vocabulary).unwrap();
+        // The puzzle's own words don't recognise "zero"...
+        assert_eq!(value("Zero7zero", Vocabulary::Words), 77);
+        assert_eq!(value("zero7", Vocabulary::Words), 77);
+        assert!(calibration_value("zero", Vocabulary::Words).is_err());
+        // ...but the extended ones do, in any case
+        assert_eq!(value("Zero7zero", Vocabulary::ExtendedWords), 0);
+        assert_eq!(value("zero7", Vocabulary::ExtendedWords), 7);
+        assert_eq!(value("7ZERO", Vocabulary::ExtendedWords), 70);
+        assert_eq!(value("xSeVeNinE", Vocabulary::ExtendedWords), 79);
+        assert_eq!(value("twOnEIGHT", Vocabulary::ExtendedWords), 28);
+        // Everything else about part b carries over
+        for line in ["two1nine", "eightwothree", "zoneight234", "7pqrstsixteen"] {
+            assert_eq!(
+                value(line, Vocabulary::ExtendedWords),
+                value(line, Vocabulary::Words),
+                "{line}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_overlapping_words() {
+        let value = |line| calibration_value(line, Vocabulary::Words).unwrap();
+        assert_eq!(value("oneight"), 18);
+        assert_eq!(value("eightwothree"), 83);
+        assert_eq!(value("twone"), 21);
+        // A word that starts partway through an unfinished one
+        assert_eq!(value("sevenine"), 79);
+        assert_eq!(value("fonineight"), 98);
+        assert_eq!(value("thrthree"), 33);
+    }
+
+    #[test]
+    fn test_words_at_line_boundaries() {
+        let value = |line| calibration_value(line, Vocabulary::Words).unwrap();
+        // A word right at the start or end of the line
+        assert_eq!(value("nine"), 99);
+        assert_eq!(value("one2"), 12);
+        assert_eq!(value("2one"), 21);
+        assert_eq!(value("eightabcthree"), 83);
+        // A word cut off by the end of the line doesn't count...
+        assert_eq!(value("4nin"), 44);
+        assert_eq!(value("ninnine"), 99);
+        assert!(calibration_value("nin", Vocabulary::Words).is_err());
+        // ...and nor does one cut off by the start of it
+        assert_eq!(value("ine4"), 44);
+        assert_eq!(value("ight5eigh"), 55);
+    }
+
+    #[test]
+    fn test_find_digits() {
+        let find = |line, vocabulary| {
+            let (first, last) = find_digits(line, vocabulary).unwrap();
+            [first, last].map(|m| (m.token, m.index, m.value))
+        };
+        assert_eq!(
+            find("two1nine", Vocabulary::Words),
+            [("two", 0, 2), ("nine", 4, 9)]
+        );
+        assert_eq!(
+            find("abceightwo", Vocabulary::Words),
+            [("eight", 3, 8), ("two", 7, 2)]
+        );
+        assert_eq!(find("treb7uchet", Vocabulary::Words), [("7", 4, 7); 2]);
+        assert_eq!(
+            find("two1nine", Vocabulary::Digits),
+            [("1", 3, 1), ("1", 3, 1)]
+        );
+        // The token is whatever was in the line, whatever its case
+        assert_eq!(
+            find("xSeVeNinE", Vocabulary::ExtendedWords),
+            [("SeVeN", 1, 7), ("NinE", 5, 9)]
+        );
+        // Indices are in bytes, which isn't the same as in characters
+        assert_eq!(
+            find("é1 café two", Vocabulary::Words),
+            [("1", 2, 1), ("two", 10, 2)]
+        );
+        assert_eq!(find_digits("xyzzy", Vocabulary::Words), None);
+    }
+
+    #[test]
+    fn test_first_and_last_digits() {
+        let first = |line| find_first_digit(line, Vocabulary::Words).map(|m| (m.index, m.value));
+        let last = |line| find_last_digit(line, Vocabulary::Words).map(|m| (m.index, m.value));
+
+        // A line that's just one digit, however it's written
+        assert_eq!((first("5"), last("5")), (Some((0, 5)), Some((0, 5))));
+        assert_eq!((first("six"), last("six")), (Some((0, 6)), Some((0, 6))));
+        // Words glued together, with or without sharing letters
+        assert_eq!(first("sixseven"), Some((0, 6)));
+        assert_eq!(last("sixseven"), Some((3, 7)));
+        assert_eq!(first("threeight"), Some((0, 3)));
+        assert_eq!(last("threeight"), Some((4, 8)));
+        // Digits between words that overlap
+        assert_eq!(first("twone1twone"), Some((0, 2)));
+        assert_eq!(last("twone1twone"), Some((8, 1)));
+        assert_eq!(first("xtwone1"), Some((1, 2)));
+        assert_eq!(last("1twonex"), Some((3, 1)));
+        // Lines that end partway through a word
+        assert_eq!(last("5seve"), Some((0, 5)));
+        assert_eq!(last("eightnin"), Some((0, 8)));
+        assert_eq!(first("ight4"), Some((4, 4)));
+        // Lines with no digits at all
+        for line in ["", "abcdef", "onf", "ten eleven twelve", "ZERO"] {
+            assert_eq!(first(line), None, "{line}");
+            assert_eq!(last(line), None, "{line}");
+        }
+        // Part a only counts the digits themselves
+        let first = |line| find_first_digit(line, Vocabulary::Digits).map(|m| m.value);
+        let last = |line| find_last_digit(line, Vocabulary::Digits).map(|m| m.value);
+        assert_eq!(
+            (first("twone1twone"), last("twone1twone")),
+            (Some(1), Some(1))
+        );
+        assert_eq!((first("sixseven"), last("sixseven")), (None, None));
+    }
+
+    #[test]
+    fn test_explaining() {
+        let m = DigitMatch {
+            token: "eight",
+            index: 3,
+            value: 8,
+        };
+        assert_eq!(m.to_string(), "\"eight\" at col 4");
+
+        let mut out = vec![];
+        let total = solve_explained(
+            "two1nine\nabceightwo\ntreb7uchet".as_bytes(),
+            Vocabulary::Words,
+            &mut out,
+        )
+        .unwrap();
+        assert_eq!(total, 29 + 82 + 77);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+two1nine: first \"two\" at col 1, last \"nine\" at col 5 => 29
+abceightwo: first \"eight\" at col 4, last \"two\" at col 8 => 82
+treb7uchet: first \"7\" at col 5, last \"7\" at col 5 => 77
+"
+        );
+
+        let error =
+            solve_explained("1abc2\nxyzzy".as_bytes(), Vocabulary::Digits, vec![]).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 ('xyzzy') contains no digits");
+    }
+
+    #[test]
+    fn test_repeated_partial_words() {
+        // Every "nin" could be the start of a "nine" (reading forwards) or the end of
+        // one (reading backwards), but neither scan needs to go back over them
+        let prefixes = "nin".repeat(10_000);
+        let value = |line: &str| calibration_value(line, Vocabulary::Words).unwrap();
+        assert_eq!(value(&format!("{prefixes}e")), 99);
+        assert_eq!(value(&format!("3{prefixes}")), 33);
+        assert_eq!(value(&format!("e{prefixes}2")), 22);
+        assert_eq!(value(&format!("one{prefixes}ine")), 19);
+        assert_eq!(value(&format!("one{prefixes}ne")), 11);
+        let line = format!("{prefixes}e{prefixes}e");
+        let (first, last) = find_digits(&line, Vocabulary::Words).unwrap();
+        assert_eq!((first.index, last.index), (29_997, 59_998));
+    }
+
+    #[test]
+    fn test_no_digits() {
+        for vocabulary in [
+            Vocabulary::Digits,
+            Vocabulary::Words,
+            Vocabulary::ExtendedWords,
+        ] {
+            assert!(calibration_value("abc", vocabulary).is_err());
+            assert!(calibration_value("", vocabulary).is_err());
+        }
+        // Only lowercase words count, unless the vocabulary is extended
+        assert!(calibration_value("ONE-Two", Vocabulary::Words).is_err());
+        assert_eq!(
+            calibration_value("ONE-Two", Vocabulary::ExtendedWords).unwrap(),
+            12
+        );
+    }
+
+    #[test]
+    fn test_line_without_digits() {
+        let error = solve(
+            "1abc2\npqr3stu8vwx\nxyzzy\ntreb7uchet".as_bytes(),
+            Vocabulary::Digits,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 3 ('xyzzy') contains no digits");
+        let error = solve(
+            "two1nine\nxyzzy\n7pqrstsixteen".as_bytes(),
+            Vocabulary::Words,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 2 ('xyzzy') contains no digits");
+        // Words that aren't digits don't count
+        let error = solve(
+            "two1nine\neightwothree\nzero ten".as_bytes(),
+            Vocabulary::Words,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "line 3 ('zero ten') contains no digits");
+        // Nor do any words at all in part a
+        let error = solve("1abc2\none".as_bytes(), Vocabulary::Digits).unwrap_err();
+        assert_eq!(error.to_string(), "line 2 ('one') contains no digits");
+
+        // The lines either side of a bad one still get their values
+        let values = Vec::from_iter(calibration_values(
+            "1abc2\nxyzzy\ntreb7uchet",
+            Vocabulary::Digits,
+        ));
+        assert_eq!(values[0].as_ref().unwrap(), &12);
+        assert!(values[1].is_err());
+        assert_eq!(values[2].as_ref().unwrap(), &77);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let input: &[u8] = b"1abc2\r\npqr3stu8vwx\r\na1b2c3d4e5f\r\ntreb7uchet\r\n";
+        assert_eq!(solve(Cursor::new(input), Vocabulary::Digits).unwrap(), 142);
+
+        // Errors reading the input are passed on, rather than panicking
+        let invalid_utf8: &[u8] = b"1abc2\n\xff\xfe\ntreb7uchet";
+        let error = solve(Cursor::new(invalid_utf8), Vocabulary::Digits).unwrap_err();
+        assert_eq!(error.to_string(), "Failed to read line 2");
+
+        struct BrokenReader;
+        impl Read for BrokenReader {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("disk on fire"))
+            }
+        }
+        let reader = std::io::BufReader::new(BrokenReader);
+        let error = solve(reader, Vocabulary::Words).unwrap_err();
+        assert_eq!(error.root_cause().to_string(), "disk on fire");
+    }
+}
@@ -0,0 +1,114 @@
+use std::fs::File;
+use std::io::BufReader;
+
+use anyhow::{bail, Context, Result};
+use day_01::{solve, solve_explained, Vocabulary};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Part {
+    /// Only ASCII digits count
+    A,
+    /// Spelled-out digits count too
+    B,
+}
+
+struct Options {
+    part: Part,
+    /// Also count "zero", and words in any case
+    extended: bool,
+    /// Print where the first and last digits were found on each line
+    explain: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            part: Part::B,
+            extended: false,
+            explain: false,
+        }
+    }
+}
+
+impl Options {
+    fn vocabulary(&self) -> Vocabulary {
+        match (self.part, self.extended) {
+            (Part::A, _) => Vocabulary::Digits,
+            (Part::B, false) => Vocabulary::Words,
+            (Part::B, true) => Vocabulary::ExtendedWords,
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                options.part = match args.next().as_deref() {
+                    Some("a") => Part::A,
+                    Some("b") => Part::B,
+                    other => bail!("Expected `a` or `b` after `--part`, got {other:?}"),
+                }
+            }
+            "--extended" => options.extended = true,
+            "--explain" => options.explain = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    if options.extended && options.part == Part::A {
+        bail!("`--extended` only makes sense for part b, which spells out digits")
+    }
+    Ok(options)
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let total = File::open("input.txt")
+        .context("Expected 'input.txt' to exist as a file!")
+        .and_then(|file| {
+            let reader = BufReader::new(file);
+            if options.explain {
+                solve_explained(reader, options.vocabulary(), std::io::stderr().lock())
+            } else {
+                solve(reader, options.vocabulary())
+            }
+        });
+    match total {
+        Ok(total) => println!("{total}"),
+        Err(error) => {
+            eprintln!("{error}");
+            std::process::exit(1)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_args, Part, Vocabulary};
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(parse(&[]).unwrap().part, Part::B);
+        assert_eq!(parse(&["--part", "a"]).unwrap().part, Part::A);
+        assert_eq!(parse(&["--part", "b"]).unwrap().part, Part::B);
+        assert!(parse(&["--part"]).is_err());
+        assert!(parse(&["--part", "c"]).is_err());
+        assert!(parse(&["-p", "a"]).is_err());
+
+        let vocabulary = |args: &[&str]| parse(args).unwrap().vocabulary();
+        assert_eq!(vocabulary(&[]), Vocabulary::Words);
+        assert_eq!(vocabulary(&["--part", "a"]), Vocabulary::Digits);
+        assert_eq!(vocabulary(&["--extended"]), Vocabulary::ExtendedWords);
+        assert_eq!(
+            vocabulary(&["--part", "b", "--extended"]),
+            Vocabulary::ExtendedWords
+        );
+        assert!(parse(&["--extended", "--part", "a"]).is_err());
+
+        assert!(!parse(&[]).unwrap().explain);
+        assert!(parse(&["--explain"]).unwrap().explain);
+        assert!(parse(&["--part", "a", "--explain"]).unwrap().explain);
+    }
+}
@@ -0,0 +1,74 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_03a::Schematic;
+use regex::Regex;
+
+/// A pseudo-random `size`x`size` schematic, mostly periods, with numbers of up to three digits
+/// and a sprinkling of symbols
+fn generate_schematic(size: usize) -> String {
+    let mut seed: u64 = 0x2023_1203;
+    let mut next = move || {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        (seed >> 33) % 100
+    };
+    let mut lines = vec![];
+    for _ in 0..size {
+        let mut line = String::with_capacity(size);
+        let mut digits_in_a_row = 0;
+        while line.len() < size {
+            let ch = match next() {
+                0..=19 if digits_in_a_row < 3 => char::from(b'0' + (next() % 10) as u8),
+                20..=29 => {
+                    ['*', '#', '+', '$', '/', '=', '@', '%', '&', '-'][(next() % 10) as usize]
+                }
+                _ => '.',
+            };
+            digits_in_a_row = if ch.is_ascii_digit() {
+                digits_in_a_row + 1
+            } else {
+                0
+            };
+            line.push(ch)
+        }
+        lines.push(line)
+    }
+    lines.join("\n")
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let input = generate_schematic(2000);
+    let mut group = c.benchmark_group("2000x2000 schematic");
+    group.sample_size(20);
+    group.bench_function("finding numbers, compiling the regex for every line", |b| {
+        b.iter(|| {
+            for line in black_box(&input).lines() {
+                // What the part a solution used to do
+                #[allow(clippy::regex_creation_in_loops)]
+                let number_re = Regex::new(r"\d+").unwrap();
+                black_box(number_re.find_iter(line).count());
+            }
+        })
+    });
+    group.bench_function("finding numbers, compiling the regex once", |b| {
+        let number_re = Regex::new(r"\d+").unwrap();
+        b.iter(|| {
+            for line in black_box(&input).lines() {
+                black_box(number_re.find_iter(line).count());
+            }
+        })
+    });
+    group.bench_function("parsing", |b| {
+        b.iter(|| black_box(&input).parse::<Schematic>().unwrap())
+    });
+    let schematic: Schematic = input.parse().unwrap();
+    group.bench_function("summing part numbers", |b| {
+        b.iter(|| black_box(&schematic).part_numbers().sum::<u32>())
+    });
+    group.finish()
+}
+
+criterion_group!(benches, bench_scan);
+criterion_main!(benches);
@@ -0,0 +1,493 @@
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A number written in the schematic, and where it was written
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Number {
+    pub value: u32,
+    pub lineno: usize,
+    /// The columns the number's digits take up
+    pub columns: Range<usize>,
+}
+
+/// Anything in the schematic other than a digit or a period
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbol {
+    pub ch: char,
+    pub lineno: usize,
+    pub column: usize,
+}
+
+impl Number {
+    /// Whether `symbol` is next to any of the number's digits, including diagonally.
+    /// That's anywhere from the column before the number's first digit to the column
+    /// after its last digit, on its own line or the lines either side.
+    pub fn is_adjacent_to(&self, symbol: &Symbol) -> bool {
+        let window = self.columns.start.saturating_sub(1)..=self.columns.end;
+        self.lineno.abs_diff(symbol.lineno) <= 1 && window.contains(&symbol.column)
+    }
+}
+
+pub fn char_is_symbol(c: &char) -> bool {
+    c != &'.' && !c.is_ascii_digit()
+}
+
+/// The engine schematic, parsed once into the numbers and symbols written in it.
+/// Nothing is looked up by slicing the lines, so they don't all have to be the same length.
+#[derive(Debug)]
+pub struct Schematic {
+    /// In the order they appear in the schematic, line by line
+    numbers: Vec<Number>,
+    /// In the order they appear in the schematic, line by line
+    symbols: Vec<Symbol>,
+}
+
+/// The items in `items` (which are sorted by line) on `lineno`
+fn on_line<T>(items: &[T], lineno: usize, line_of: impl Fn(&T) -> usize) -> &[T] {
+    let start = items.partition_point(|item| line_of(item) < lineno);
+    let end = items.partition_point(|item| line_of(item) <= lineno);
+    &items[start..end]
+}
+
+/// `lineno`, and the lines either side of it
+fn neighbouring_lines(lineno: usize) -> RangeInclusive<usize> {
+    lineno.saturating_sub(1)..=lineno + 1
+}
+
+impl Schematic {
+    pub fn numbers(&self) -> &[Number] {
+        &self.numbers
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    pub fn symbols_adjacent_to_number<'a>(
+        &'a self,
+        number: &'a Number,
+    ) -> impl Iterator<Item = &'a Symbol> {
+        // Each line's symbols are sorted by column,
+        // so skip straight to the ones that might be next to the number
+        neighbouring_lines(number.lineno).flat_map(move |lineno| {
+            let line = on_line(&self.symbols, lineno, |symbol| symbol.lineno);
+            let start = line.partition_point(|symbol| symbol.column + 1 < number.columns.start);
+            line[start..]
+                .iter()
+                .take_while(|symbol| symbol.column <= number.columns.end)
+        })
+    }
+
+    pub fn numbers_adjacent_to_symbol<'a>(
+        &'a self,
+        symbol: &'a Symbol,
+    ) -> impl Iterator<Item = &'a Number> {
+        // Numbers on the same line can't overlap, so each line's numbers are sorted
+        // by where they end as well as by where they start
+        neighbouring_lines(symbol.lineno).flat_map(move |lineno| {
+            let line = on_line(&self.numbers, lineno, |number| number.lineno);
+            let start = line.partition_point(|number| number.columns.end < symbol.column);
+            line[start..]
+                .iter()
+                .take_while(|number| number.columns.start <= symbol.column + 1)
+        })
+    }
+
+    /// The numbers next to at least one symbol for which `predicate` is true,
+    /// in the order they appear in the schematic
+    pub fn numbers_adjacent_to(&self, predicate: impl Fn(char) -> bool) -> Vec<&Number> {
+        Vec::from_iter(self.numbers.iter().filter(|number| {
+            self.symbols_adjacent_to_number(number)
+                .any(|symbol| predicate(symbol.ch))
+        }))
+    }
+
+    /// The numbers next to at least one symbol, along with where they are in the schematic
+    pub fn located_part_numbers(&self) -> Vec<&Number> {
+        self.numbers_adjacent_to(|_| true)
+    }
+
+    /// The numbers next to at least one symbol (part a)
+    pub fn part_numbers(&self) -> impl Iterator<Item = u32> + '_ {
+        self.located_part_numbers()
+            .into_iter()
+            .map(|number| number.value)
+    }
+
+    /// `input` (the text this schematic was parsed from) with the digits of every number
+    /// that isn't a part number replaced by periods, so only the part numbers are left
+    pub fn render_part_numbers(&self, input: &str) -> String {
+        let mut lines = Vec::from_iter(input.lines().map(|line| line.as_bytes().to_vec()));
+        for number in &self.numbers {
+            if self.symbols_adjacent_to_number(number).next().is_none() {
+                lines[number.lineno][number.columns.clone()].fill(b'.')
+            }
+        }
+        let lines = lines.into_iter().map(|line| {
+            String::from_utf8(line).expect("Expected the schematic to have been checked for ASCII")
+        });
+        Vec::from_iter(lines).join("\n")
+    }
+
+    /// The pair of numbers next to each `*` that's next to exactly two numbers (part b)
+    pub fn gears(&self) -> impl Iterator<Item = (u32, u32)> + '_ {
+        self.symbols
+            .iter()
+            .filter(|symbol| symbol.ch == '*')
+            .filter_map(|symbol| {
+                let adjacent = Vec::from_iter(self.numbers_adjacent_to_symbol(symbol).take(3));
+                match adjacent[..] {
+                    [first, second] => Some((first.value, second.value)),
+                    _ => None,
+                }
+            })
+    }
+}
+
+impl FromStr for Schematic {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        static NUMBER_RE: Lazy<Regex> =
+            Lazy::new(|| Regex::new(r"\d+").expect("Thought this would be a valid regex"));
+        let mut numbers = vec![];
+        let mut symbols = vec![];
+        for (lineno, line) in s.lines().enumerate() {
+            // Columns are counted in bytes, which is only the same as counting characters
+            // if every character is ASCII
+            if let Some((column, ch)) = line.chars().enumerate().find(|(_, ch)| !ch.is_ascii()) {
+                bail!(
+                    "Expected the schematic to be ASCII, but line {} has {ch:?} at column {}",
+                    lineno + 1,
+                    column + 1
+                )
+            }
+            for needle in NUMBER_RE.find_iter(line) {
+                let value = needle.as_str().parse().with_context(|| {
+                    format!(
+                        "The number on line {} at column {} is too big",
+                        lineno + 1,
+                        needle.start() + 1
+                    )
+                })?;
+                numbers.push(Number {
+                    value,
+                    lineno,
+                    columns: needle.range(),
+                })
+            }
+            symbols.extend(
+                line.char_indices()
+                    .filter(|(_, ch)| char_is_symbol(ch))
+                    .map(|(column, ch)| Symbol { ch, lineno, column }),
+            )
+        }
+        Ok(Schematic { numbers, symbols })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{char_is_symbol, Number, Schematic, Symbol};
+
+    const EXAMPLE: &str = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+    #[test]
+    fn test_char_is_symbol() {
+        for c in ['*', '#', '+', '$', '/', '=', '@', '%', '&', '-'] {
+            assert!(char_is_symbol(&c), "{c}")
+        }
+        for c in ['.', '0', '5', '9'] {
+            assert!(!char_is_symbol(&c), "{c}")
+        }
+    }
+
+    #[test]
+    fn test_parsing() {
+        let schematic: Schematic = EXAMPLE.parse().unwrap();
+        assert_eq!(schematic.numbers().len(), 10);
+        assert_eq!(
+            schematic.numbers()[2],
+            Number {
+                value: 35,
+                lineno: 2,
+                columns: 2..4
+            }
+        );
+        assert_eq!(schematic.symbols().len(), 6);
+        assert_eq!(
+            schematic.symbols()[3],
+            Symbol {
+                ch: '+',
+                lineno: 5,
+                column: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_example() {
+        let schematic: Schematic = EXAMPLE.parse().unwrap();
+        assert_eq!(
+            Vec::from_iter(schematic.part_numbers()),
+            [467, 35, 633, 617, 592, 755, 664, 598]
+        );
+        assert_eq!(schematic.part_numbers().sum::<u32>(), 4361);
+        assert_eq!(Vec::from_iter(schematic.gears()), [(467, 35), (755, 598)]);
+        assert_eq!(schematic.gears().map(|(a, b)| a * b).sum::<u32>(), 467835);
+    }
+
+    #[test]
+    fn test_located_part_numbers() {
+        let schematic: Schematic = EXAMPLE.parse().unwrap();
+        let located = schematic.located_part_numbers();
+        assert_eq!(located.len(), 8);
+        assert_eq!(
+            located[2],
+            &Number {
+                value: 633,
+                lineno: 2,
+                columns: 6..9
+            }
+        );
+        assert_eq!(
+            located[7],
+            &Number {
+                value: 598,
+                lineno: 9,
+                columns: 5..8
+            }
+        );
+    }
+
+    #[test]
+    fn test_render_part_numbers() {
+        let schematic: Schematic = EXAMPLE.parse().unwrap();
+        let expected = "\
+467.......
+...*......
+..35..633.
+......#...
+617*......
+.....+....
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!(schematic.render_part_numbers(EXAMPLE), expected);
+        // Numbers on ragged lines are blanked out too
+        let input = "1\n..*\n...23\n45";
+        let schematic: Schematic = input.parse().unwrap();
+        assert_eq!(schematic.render_part_numbers(input), ".\n..*\n...23\n..");
+    }
+
+    #[test]
+    fn test_adjacency() {
+        let number = Number {
+            value: 123,
+            lineno: 5,
+            columns: 3..6,
+        };
+        let symbol = |lineno, column| Symbol {
+            ch: '#',
+            lineno,
+            column,
+        };
+        for lineno in 4..=6 {
+            for column in 2..=6 {
+                assert!(
+                    number.is_adjacent_to(&symbol(lineno, column)),
+                    "{lineno}, {column}"
+                )
+            }
+        }
+        for (lineno, column) in [(3, 4), (7, 4), (5, 1), (5, 7), (4, 7), (6, 1)] {
+            assert!(
+                !number.is_adjacent_to(&symbol(lineno, column)),
+                "{lineno}, {column}"
+            )
+        }
+        // A number in the top-left corner
+        let number = Number {
+            value: 12,
+            lineno: 0,
+            columns: 0..2,
+        };
+        for (lineno, column) in [(0, 2), (1, 0), (1, 1), (1, 2)] {
+            assert!(
+                number.is_adjacent_to(&symbol(lineno, column)),
+                "{lineno}, {column}"
+            )
+        }
+        for (lineno, column) in [(0, 3), (1, 3), (2, 0)] {
+            assert!(
+                !number.is_adjacent_to(&symbol(lineno, column)),
+                "{lineno}, {column}"
+            )
+        }
+    }
+
+    #[test]
+    fn test_adjacency_queries() {
+        let schematic: Schematic = EXAMPLE.parse().unwrap();
+        let values = |numbers: Vec<&Number>| Vec::from_iter(numbers.iter().map(|n| n.value));
+        assert_eq!(values(schematic.numbers_adjacent_to(|ch| ch == '$')), [664]);
+        assert_eq!(values(schematic.numbers_adjacent_to(|ch| ch == '+')), [592]);
+        assert_eq!(values(schematic.numbers_adjacent_to(|ch| ch == '#')), [633]);
+        assert_eq!(values(schematic.numbers_adjacent_to(|ch| ch == '@')), []);
+        assert_eq!(
+            values(schematic.numbers_adjacent_to(|ch| ch == '*')),
+            [467, 35, 617, 755, 598]
+        );
+
+        // Diagonally adjacent symbols on either side, but not ones two columns or lines away
+        let schematic: Schematic = "\
+$.....
+.123.%
+....&.
+..=..."
+            .parse()
+            .unwrap();
+        let number = &schematic.numbers()[0];
+        let symbols = Vec::from_iter(
+            schematic
+                .symbols_adjacent_to_number(number)
+                .map(|symbol| symbol.ch),
+        );
+        assert_eq!(symbols, ['$', '&']);
+        // Numbers touching at least two different symbols
+        let schematic: Schematic = "\
+1*2.3
+.#...
+4..5$"
+            .parse()
+            .unwrap();
+        let touching_two_kinds = Vec::from_iter(schematic.numbers().iter().filter(|number| {
+            let symbols: std::collections::HashSet<char> = schematic
+                .symbols_adjacent_to_number(number)
+                .map(|symbol| symbol.ch)
+                .collect();
+            symbols.len() >= 2
+        }));
+        assert_eq!(values(touching_two_kinds), [1, 2]);
+        let symbol = &schematic.symbols()[0];
+        assert_eq!(
+            Vec::from_iter(
+                schematic
+                    .numbers_adjacent_to_symbol(symbol)
+                    .map(|n| n.value)
+            ),
+            [1, 2]
+        );
+    }
+
+    #[test]
+    fn test_numbers_on_the_edges() {
+        let part_numbers =
+            |input: &str| Vec::from_iter(input.parse::<Schematic>().unwrap().part_numbers());
+        // Symbols diagonally next to numbers at either end of the top and bottom lines
+        assert_eq!(
+            part_numbers("12....34\n..#..#..\n56....78"),
+            [12, 34, 56, 78]
+        );
+        // Symbols right next to them on the same line
+        assert_eq!(part_numbers("1*....*2"), [1, 2]);
+        // A symbol two columns away doesn't count
+        assert_eq!(part_numbers("12.....\n...#...\n.....34"), []);
+        // Nor does a symbol on a line that isn't next to the number
+        assert_eq!(part_numbers("12\n..\n*."), []);
+    }
+
+    #[test]
+    fn test_numbers_hugging_the_edges() {
+        let part_numbers =
+            |input: &str| Vec::from_iter(input.parse::<Schematic>().unwrap().part_numbers());
+        // The left edge: there's no column before the number for the window to include
+        assert_eq!(part_numbers("12..\n#..."), [12]);
+        assert_eq!(part_numbers("12..\n..#."), [12]);
+        assert_eq!(part_numbers("12..\n...#"), []);
+        assert_eq!(part_numbers("#...\n12.."), [12]);
+        // The right edge: the column after the number is past the end of its line,
+        // but can still be on a longer line above or below
+        assert_eq!(part_numbers("..12\n.#.."), [12]);
+        assert_eq!(part_numbers("..12\n#..."), []);
+        assert_eq!(part_numbers("..12\n....#"), [12]);
+        assert_eq!(part_numbers("..12\n.....#"), []);
+        assert_eq!(part_numbers("....#\n..12"), [12]);
+        // All four corners
+        assert_eq!(
+            part_numbers("1...2\n.*.*.\n.....\n.*.*.\n3...4"),
+            [1, 2, 3, 4]
+        );
+        assert_eq!(part_numbers("1...2\n..*..\n3...4"), []);
+        assert_eq!(part_numbers("12*34\n.....\n56*78"), [12, 34, 56, 78]);
+    }
+
+    #[test]
+    fn test_ragged_lines() {
+        let part_numbers =
+            |input: &str| Vec::from_iter(input.parse::<Schematic>().unwrap().part_numbers());
+        // The last line is one character shorter than the others
+        let schematic = "\
+..........
+.......123
+........*";
+        assert_eq!(part_numbers(schematic), [123]);
+        let schematic = "\
+.......123
+..........
+.........";
+        assert_eq!(part_numbers(schematic), []);
+        // A symbol beyond the end of the line above or below still counts
+        assert_eq!(part_numbers("12\n..#\n.1"), [12, 1]);
+        assert_eq!(part_numbers("..\n...45\n.....#"), [45]);
+        // Lines of all sorts of lengths, some of them empty
+        let schematic = "\
+467..114..
+...*
+
+..35..633.
+......#";
+        assert_eq!(part_numbers(schematic), [467, 633]);
+        let gears = |input: &str| Vec::from_iter(input.parse::<Schematic>().unwrap().gears());
+        assert_eq!(gears("..\n.......12\n........*\n.......3"), [(12, 3)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = "......\n..4294967296*1".parse::<Schematic>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The number on line 2 at column 3 is too big"
+        );
+        let error = "467..114..\n...*..·...".parse::<Schematic>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected the schematic to be ASCII, but line 2 has '·' at column 7"
+        );
+        let error = "\u{feff}467..114..\n...*......"
+            .parse::<Schematic>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected the schematic to be ASCII, but line 1 has '\\u{feff}' at column 1"
+        );
+        // The largest number that fits is fine
+        let schematic: Schematic = "4294967295*".parse().unwrap();
+        assert_eq!(Vec::from_iter(schematic.part_numbers()), [u32::MAX]);
+    }
+}
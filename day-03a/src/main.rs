@@ -1,75 +1,69 @@
-use std::cmp::min;
-use std::collections::HashSet;
 use std::fs::read_to_string;
-use std::ops::Range;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
+use anyhow::{bail, Result};
+use day_03a::Schematic;
 
-fn gather_surrounding_chars(
-    loc_range: Range<usize>,
-    lineno: usize,
-    line: &str,
-    all_lines: &[&str],
-) -> HashSet<char> {
-    let left = loc_range.start.saturating_sub(1);
-    let right = min(all_lines[0].len() - 1, loc_range.end);
-    let mut answer = HashSet::new();
-    if let Some(prev_line) = all_lines.get(lineno.saturating_sub(1)) {
-        answer.extend(prev_line[left..=right].chars());
-    }
-    if let Some(next_line) = all_lines.get(lineno + 1) {
-        answer.extend(next_line[left..=right].chars());
-    }
-    let line_as_bytes = line.as_bytes();
-    answer.insert(line_as_bytes[left].into());
-    answer.insert(line_as_bytes[right].into());
-    answer
+#[derive(Default)]
+struct Options {
+    /// Print the schematic with every number that isn't a part number blanked out
+    verbose: bool,
 }
 
-fn char_is_symbol(c: &char) -> bool {
-    c != &'.' && !c.is_ascii_digit()
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    for arg in args {
+        match arg.as_str() {
+            "--verbose" => options.verbose = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
 }
 
-fn is_part_number(loc_range: Range<usize>, lineno: usize, line: &str, all_lines: &[&str]) -> bool {
-    gather_surrounding_chars(loc_range, lineno, line, all_lines)
-        .iter()
-        .any(char_is_symbol)
+fn read_input(filename: &str) -> String {
+    read_to_string(filename).unwrap_or_else(|_| panic!("Expected {filename} to exist"))
 }
 
-fn gather_part_numbers_from_line(lineno: usize, line: &str, all_lines: &[&str]) -> Vec<u32> {
-    static NUMBER_RE: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"\d+").expect("Thought this would be a valid regex"));
-    NUMBER_RE
-        .find_iter(line)
-        .filter(|needle| is_part_number(needle.range(), lineno, line, all_lines))
-        .map(|needle| {
-            needle
-                .as_str()
-                .parse()
-                .expect("Expected this to parse as a number")
-        })
-        .collect()
+fn solve(input: &str, options: &Options) -> Result<u32> {
+    let schematic: Schematic = input.parse()?;
+    if options.verbose {
+        eprintln!("{}", schematic.render_part_numbers(input))
+    }
+    Ok(schematic.part_numbers().sum())
 }
 
-fn gather_part_numbers_from_file(input: String) -> Vec<u32> {
-    let lines: Vec<&str> = input.lines().collect();
-    lines
-        .iter()
-        .enumerate()
-        .flat_map(|(lineno, line)| gather_part_numbers_from_line(lineno, line, &lines))
-        .collect()
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    println!("{}", solve(&read_input("input.txt"), &options).unwrap());
 }
 
-fn read_input(filename: &str) -> String {
-    read_to_string(filename).unwrap_or_else(|_| panic!("Expected {filename} to exist"))
-}
+#[cfg(test)]
+mod tests {
+    use crate::{parse_args, solve, Options};
 
-fn solve(filename: &str) -> u32 {
-    let input = read_input(filename);
-    gather_part_numbers_from_file(input).iter().sum()
-}
+    #[test]
+    fn test_example() {
+        let example = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!(solve(example, &Options::default()).unwrap(), 4361);
+        let verbose = Options { verbose: true };
+        assert_eq!(solve(example, &verbose).unwrap(), 4361)
+    }
 
-fn main() {
-    println!("{}", solve("input.txt"));
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(!parse(&[]).unwrap().verbose);
+        assert!(parse(&["--verbose"]).unwrap().verbose);
+        assert!(parse(&["-v"]).is_err());
+    }
 }
@@ -1,61 +1,94 @@
-use std::cmp::min;
 use std::fs::read_to_string;
 
-use once_cell::sync::Lazy;
-use regex::Regex;
+use anyhow::Result;
+use day_03a::Schematic;
 
 fn read_input(filename: &str) -> String {
     read_to_string(filename).unwrap_or_else(|_| panic!("Expected {filename} to exist"))
 }
 
-fn get_gear_ratio(index: usize, all_lines: &[&str], lineno: usize, line_length: usize) -> u32 {
-    let line = all_lines[lineno];
-    let c = line.chars().nth(index).unwrap();
-    if c != '*' {
-        return 0;
-    }
-    static NUMBER_RE: Lazy<Regex> =
-        Lazy::new(|| Regex::new(r"\d{1,3}").expect("Expected this to be a valid regex"));
-    let range_to_search = index.saturating_sub(3)..=min(index + 3, line_length);
-    let haystacks = [
-        &line[range_to_search.clone()],
-        &all_lines[lineno - 1][range_to_search.clone()],
-        &all_lines[lineno + 1][range_to_search],
-    ];
-    let matches: Vec<_> = haystacks
-        .iter()
-        .flat_map(|haystack| NUMBER_RE.find_iter(haystack))
-        .filter(|m| (2..=4).any(|i| m.range().contains(&i)))
-        .take(3)
-        .collect();
-    if matches.len() != 2 {
-        return 0;
-    }
-    matches
-        .iter()
-        .map(|m| {
-            m.as_str()
-                .parse::<u32>()
-                .expect("Expected all matches to parse as integers")
-        })
-        .product()
+fn solve(input: &str) -> Result<u64> {
+    let schematic: Schematic = input.parse()?;
+    // Part numbers can go up to `u32::MAX`, so their product might not fit in a `u32`
+    Ok(schematic
+        .gears()
+        .map(|(a, b)| u64::from(a) * u64::from(b))
+        .sum())
 }
 
-fn get_gear_ratio_sum_in_line(all_lines: &[&str], lineno: usize, line_length: usize) -> u32 {
-    (0..line_length)
-        .map(|index| get_gear_ratio(index, all_lines, lineno, line_length))
-        .sum()
+fn main() {
+    println!("{}", solve(&read_input("input.txt")).unwrap());
 }
 
-fn solve(filename: &str) -> u32 {
-    let input = read_input(filename);
-    let lines: Vec<&str> = input.lines().collect();
-    let line_length = lines[0].len();
-    (1..(lines.len() - 1))
-        .map(|lineno| get_gear_ratio_sum_in_line(&lines, lineno, line_length))
-        .sum()
-}
+#[cfg(test)]
+mod tests {
+    use crate::solve;
 
-fn main() {
-    println!("{}", solve("input.txt"));
+    #[test]
+    fn test_example() {
+        let example = "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+        assert_eq!(solve(example).unwrap(), 467835)
+    }
+
+    #[test]
+    fn test_gears_on_the_edge_lines() {
+        // The only gear is on the top row
+        assert_eq!(solve("..12*34...\n..........\n..........").unwrap(), 408);
+        assert_eq!(solve("....*.....\n...5.7....\n..........").unwrap(), 35);
+        // ...or on the bottom row
+        assert_eq!(solve("..........\n..........\n..12*34...").unwrap(), 408);
+        assert_eq!(solve("..........\n...5.7....\n....*.....").unwrap(), 35);
+        // ...or the schematic is only one line long
+        assert_eq!(solve("..12*34...").unwrap(), 408);
+        // Still not a gear if it's next to just one number
+        assert_eq!(solve("..12*.....\n..........").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_long_numbers() {
+        assert_eq!(solve("1234*5678").unwrap(), 1234 * 5678);
+        assert_eq!(solve("....12345.\n.........*\n........2.").unwrap(), 24690);
+        // A number ending just before the gear's column on the line above
+        assert_eq!(solve("1000......\n....*.....\n.....3....").unwrap(), 3000);
+        // ...or starting two columns after it is too far away
+        assert_eq!(solve("......1000\n....*.....\n...3......").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_gear_ratios_bigger_than_u32() {
+        assert_eq!(solve("65536*65536").unwrap(), 1 << 32);
+        assert_eq!(
+            solve("4294967295*4294967295").unwrap(),
+            u64::from(u32::MAX) * u64::from(u32::MAX)
+        );
+        // The sum can outgrow a `u32` even when every ratio fits in one
+        let gear = "65535*65535";
+        let input = [gear; 2].join("\n.\n");
+        assert_eq!(solve(&input).unwrap(), 2 * 65535 * 65535);
+    }
+
+    #[test]
+    fn test_gears_near_the_start_of_a_line() {
+        assert_eq!(solve("5.....\n.*....\n..7...").unwrap(), 35);
+        assert_eq!(solve("12....\n*.....\n3.....").unwrap(), 36);
+        assert_eq!(solve("*12\n34.").unwrap(), 408);
+        // Two columns to the right of a gear in the first column is too far
+        assert_eq!(solve("*.12\n3...").unwrap(), 0);
+    }
+
+    #[test]
+    fn test_non_ascii_schematic() {
+        let error = solve("467..114..\n...*..·...").unwrap_err();
+        assert!(error.to_string().contains("line 2"), "{error}");
+    }
 }
@@ -68,6 +68,18 @@ impl InputDataRow {
         self.source_start..(self.source_start + self.range_length)
     }
 
+    /// Whether any source value is covered by both rows
+    fn overlaps(&self, other: &InputDataRow) -> bool {
+        !find_range_overlap(&self.source_range(), &other.source_range()).is_empty()
+    }
+
+    /// Whether one row's source range carries on exactly where the other's ends
+    #[cfg(test)]
+    fn adjacent(&self, other: &InputDataRow) -> bool {
+        let (x, y) = (self.source_range(), other.source_range());
+        x.end == y.start || y.end == x.start
+    }
+
     fn convert_single(&self, item: u64) -> u64 {
         let source_range = self.source_range();
         assert!(source_range.contains(&item) || item == source_range.end);
@@ -120,10 +132,17 @@ impl FromStr for InputMap {
                     .next()
                     .context("Expected the first line to have two or more words!")?;
                 let kind: MapKind = kind_description.parse()?;
-                let rows = unparsed_rows
+                let rows: Vec<InputDataRow> = unparsed_rows
                     .iter()
                     .map(|s| s.parse())
                     .collect::<Result<_>>()?;
+                // Each source value should be converted by at most one row
+                debug_assert!(
+                    rows.iter()
+                        .array_combinations()
+                        .all(|[x, y]| !x.overlaps(y)),
+                    "Expected the rows of the {first_line:?} map not to overlap"
+                );
                 Ok(InputMap { kind, rows })
             }
             _ => bail!("Couldn't construct an InputMap from {s}"),
@@ -140,6 +159,40 @@ struct RangeMap {
     mapping: HashMap<Range<u64>, Range<u64>>,
 }
 
+impl RangeMap {
+    /// The mapped ranges, in order of where their destination ranges start
+    fn sorted_ranges(&self) -> Vec<(&Range<u64>, &Range<u64>)> {
+        let mut ranges = self.mapping.iter().collect_vec();
+        ranges.sort_unstable_by_key(|(_, destination)| (destination.start, destination.end));
+        ranges
+    }
+
+    /// Every (source, destination) pair in the map, one range at a time,
+    /// in order of where each destination range starts.
+    /// The pairs are only generated as they're needed, since there can be billions of them.
+    fn iter_pairs(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.sorted_ranges()
+            .into_iter()
+            .flat_map(|(source, destination)| zip(source.clone(), destination.clone()))
+    }
+
+    /// Every destination value in the map. The first one is the lowest.
+    fn iter_locations(&self) -> impl Iterator<Item = u64> + '_ {
+        self.iter_pairs().map(|(_, location)| location)
+    }
+
+    /// Every source value that ends up somewhere below `limit`
+    fn sources_below(&self, limit: u64) -> impl Iterator<Item = u64> + '_ {
+        self.sorted_ranges()
+            .into_iter()
+            .take_while(move |(_, destination)| destination.start < limit)
+            .flat_map(move |(source, destination)| {
+                let below = min(destination.end, limit) - destination.start;
+                source.start..(source.start + below)
+            })
+    }
+}
+
 #[cfg(debug_assertions)]
 fn _check_range_mapping_consistency(
     initial: &HashMap<Range<u64>, Range<u64>>,
@@ -268,6 +321,7 @@ impl FromStr for InputData {
                     bail!("Expected there to be 2 or more maps!")
                 }
                 let seed_ranges = parse_seed_ranges_from_input(unparsed_seeds)?;
+                validate_no_overlaps(&seed_ranges)?;
                 let maps = unparsed_maps
                     .iter()
                     .map(|s| s.parse())
@@ -319,17 +373,195 @@ fn parse_seed_ranges_from_input(seed_description: &str) -> Result<Vec<Range<u64>
         .collect()
 }
 
-fn solve(filename: &str) -> u64 {
-    let input_data = parse_input(filename);
+/// The range mapping assumes each seed belongs to only one seed range,
+/// so it would silently give wrong answers if any of the ranges overlapped
+fn validate_no_overlaps(ranges: &[Range<u64>]) -> Result<()> {
+    for [x, y] in ranges.iter().array_combinations() {
+        if !find_range_overlap(x, y).is_empty() {
+            bail!("Expected the seed ranges not to overlap, but {x:?} and {y:?} do")
+        }
+    }
+    Ok(())
+}
+
+fn solve(input_data: InputData) -> u64 {
     let range_map = seedrange_to_locationrange(input_data);
-    range_map
-        .mapping
-        .values()
-        .min_by_key(|r| r.start)
-        .unwrap()
-        .start
+    let lowest_location = range_map.iter_locations().next();
+    lowest_location.expect("Expected at least one seed to have a location")
+}
+
+#[derive(Default)]
+struct Options {
+    /// List the seeds that end up at a location below this, instead of solving the puzzle
+    seeds_below: Option<u64>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seeds-below" => {
+                let Some(limit) = args.next() else {
+                    bail!("Expected a location after `--seeds-below`")
+                };
+                options.seeds_below = Some(
+                    limit
+                        .parse()
+                        .with_context(|| format!("Invalid location: {limit}"))?,
+                )
+            }
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
 }
 
 fn main() {
-    println!("{}", solve("input.txt"));
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input_data = parse_input("input.txt");
+    match options.seeds_below {
+        Some(limit) => {
+            let range_map = seedrange_to_locationrange(input_data);
+            for seed in range_map.sources_below(limit) {
+                println!("{seed}")
+            }
+        }
+        None => println!("{}", solve(input_data)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::{
+        parse_args, seedrange_to_locationrange, solve, validate_no_overlaps, InputData,
+        InputDataRow,
+    };
+
+    const EXAMPLE: &str = "\
+seeds: 79 14 55 13
+
+seed-to-soil map:
+50 98 2
+52 50 48
+
+soil-to-fertilizer map:
+0 15 37
+37 52 2
+39 0 15
+
+fertilizer-to-water map:
+49 53 8
+0 11 42
+42 0 7
+57 7 4
+
+water-to-light map:
+88 18 7
+18 25 70
+
+light-to-temperature map:
+45 77 23
+81 45 19
+68 64 13
+
+temperature-to-humidity map:
+0 69 1
+1 0 69
+
+humidity-to-location map:
+60 56 37
+56 93 4";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE.parse().unwrap()), 46)
+    }
+
+    #[test]
+    fn test_iterating_over_the_range_map() {
+        let range_map = seedrange_to_locationrange(EXAMPLE.parse().unwrap());
+        // Seed 82 is the one that ends up at location 46
+        let mut pairs = range_map.iter_pairs();
+        assert_eq!(pairs.next(), Some((82, 46)));
+        assert_eq!(range_map.iter_locations().next(), Some(46));
+        assert_eq!(range_map.iter_locations().count(), 14 + 13);
+        // Every seed shows up exactly once
+        let mut seeds = range_map.iter_pairs().map(|(seed, _)| seed).collect_vec();
+        seeds.sort();
+        assert_eq!(seeds, (55..68).chain(79..93).collect_vec());
+        // Seeds 82 to 84 end up at locations 46 to 48...
+        assert_eq!(range_map.sources_below(46).count(), 0);
+        assert_eq!(
+            range_map.sources_below(49).sorted().collect_vec(),
+            [82, 83, 84]
+        );
+        // ...and the rest of them are higher than that
+        assert_eq!(range_map.sources_below(u64::MAX).count(), 27);
+    }
+
+    #[test]
+    fn test_validate_no_overlaps() {
+        assert!(validate_no_overlaps(&[]).is_ok());
+        assert!(validate_no_overlaps(&[79..93, 55..68]).is_ok());
+        // Ranges that only touch don't overlap
+        assert!(validate_no_overlaps(&[10..20, 20..30, 0..10]).is_ok());
+        // Nor does an empty range inside another
+        assert!(validate_no_overlaps(&[10..20, 15..15]).is_ok());
+
+        let error = validate_no_overlaps(&[10..20, 30..40, 19..25]).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Expected the seed ranges not to overlap, but 10..20 and 19..25 do"
+        );
+        assert!(validate_no_overlaps(&[0..100, 40..50]).is_err());
+        assert!(validate_no_overlaps(&[5..10, 5..10]).is_err());
+    }
+
+    #[test]
+    fn test_overlapping_seed_ranges_are_rejected() {
+        let input_data: InputData = EXAMPLE.parse().unwrap();
+        assert_eq!(input_data.seed_ranges, [79..93, 55..68]);
+        let overlapping = EXAMPLE.replace("seeds: 79 14 55 13", "seeds: 79 14 90 13");
+        let error = overlapping.parse::<InputData>().err().unwrap();
+        assert!(error.to_string().contains("79..93 and 90..103"), "{error}");
+    }
+
+    #[test]
+    fn test_row_overlaps() {
+        let row = |s: &str| s.parse::<InputDataRow>().unwrap();
+        // Source ranges 98..100, 50..98 and 90..95
+        let (a, b, c) = (row("50 98 2"), row("52 50 48"), row("0 90 5"));
+        // Rows that only touch don't overlap
+        assert!(!a.overlaps(&b) && !b.overlaps(&a));
+        assert!(a.adjacent(&b) && b.adjacent(&a));
+        assert!(b.overlaps(&c) && c.overlaps(&b));
+        assert!(!b.adjacent(&c));
+        assert!(!a.overlaps(&c) && !a.adjacent(&c));
+        assert!(a.overlaps(&a));
+        // A row that converts nothing doesn't overlap anything
+        let empty = row("0 95 0");
+        assert!(!empty.overlaps(&b) && !empty.overlaps(&empty));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not to overlap")]
+    fn test_overlapping_rows_are_caught() {
+        let overlapping = EXAMPLE.replace("0 15 37\n37 52 2", "0 15 38\n37 52 2");
+        let _ = overlapping.parse::<InputData>();
+    }
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(parse(&[]).unwrap().seeds_below, None);
+        assert_eq!(
+            parse(&["--seeds-below", "49"]).unwrap().seeds_below,
+            Some(49)
+        );
+        assert!(parse(&["--seeds-below"]).is_err());
+        assert!(parse(&["--seeds-below", "-1"]).is_err());
+        assert!(parse(&["--locations"]).is_err());
+    }
 }
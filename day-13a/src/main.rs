@@ -23,12 +23,15 @@ fn reversed_slice(seq: &[String], i: usize, upper: usize) -> Vec<String> {
     slice
 }
 
-fn find_score(pattern: &[String]) -> u32 {
+/// Returns the score of every line of symmetry in the pattern
+/// (the puzzle guarantees there's exactly one, but it's cheap to check)
+fn find_all_scores(pattern: &[String]) -> Vec<u32> {
+    let mut scores = vec![];
     let num_rows = pattern.len();
     for i in 1..num_rows {
         let (upper, lower) = upper_and_lower(i, num_rows);
         if pattern[lower..i] == reversed_slice(pattern, i, upper)[..] {
-            return (i * 100).try_into().unwrap();
+            scores.push((i * 100).try_into().unwrap());
         }
     }
 
@@ -42,17 +45,63 @@ fn find_score(pattern: &[String]) -> u32 {
     for i in 1..num_columns {
         let (upper, lower) = upper_and_lower(i, num_columns);
         if columns[lower..i] == reversed_slice(&columns, i, upper)[..] {
-            return i.try_into().unwrap();
+            scores.push(i.try_into().unwrap());
         }
     }
 
-    unreachable!("Should be unreachable!")
+    scores
 }
 
 fn solve(filename: &str) -> u32 {
-    parse_input(filename).iter().map(|p| find_score(p)).sum()
+    parse_input(filename)
+        .iter()
+        .map(|p| {
+            let scores = find_all_scores(p);
+            assert_eq!(
+                scores.len(),
+                1,
+                "Expected exactly one line of symmetry in this pattern:\n{}",
+                p.join("\n")
+            );
+            scores[0]
+        })
+        .sum()
 }
 
 fn main() {
     println!("{}", solve("input.txt"));
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::find_all_scores;
+
+    const EXAMPLE: [&str; 2] = [
+        "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.",
+        "\
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#",
+    ];
+
+    fn pattern(s: &str) -> Vec<String> {
+        Vec::from_iter(s.lines().map(|line| line.to_string()))
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(find_all_scores(&pattern(EXAMPLE[0])), [5]);
+        assert_eq!(find_all_scores(&pattern(EXAMPLE[1])), [400]);
+    }
+}
@@ -39,7 +39,10 @@ fn is_match(left: &[RowOrColumn], right: &[RowOrColumn]) -> bool {
     nearly_equal_one_found
 }
 
-fn find_score(pattern: &[String]) -> u32 {
+/// Returns the score of every line of symmetry in the pattern
+/// (the puzzle guarantees there's exactly one, but it's cheap to check)
+fn find_all_scores(pattern: &[String]) -> Vec<u32> {
+    let mut scores = vec![];
     let num_rows = pattern.len();
 
     let rows: Vec<RowOrColumn> = pattern
@@ -49,7 +52,7 @@ fn find_score(pattern: &[String]) -> u32 {
     for i in 1..num_rows {
         let (upper, lower) = upper_and_lower(i, num_rows);
         if is_match(&rows[lower..i], &rows[i..upper]) {
-            return (i * 100).try_into().unwrap();
+            scores.push((i * 100).try_into().unwrap());
         }
     }
 
@@ -66,17 +69,63 @@ fn find_score(pattern: &[String]) -> u32 {
     for i in 1..num_columns {
         let (upper, lower) = upper_and_lower(i, num_columns);
         if is_match(&columns[lower..i], &columns[i..upper]) {
-            return i.try_into().unwrap();
+            scores.push(i.try_into().unwrap());
         }
     }
 
-    unreachable!("Should be unreachable!")
+    scores
 }
 
 fn solve(filename: &str) -> u32 {
-    parse_input(filename).iter().map(|p| find_score(p)).sum()
+    parse_input(filename)
+        .iter()
+        .map(|p| {
+            let scores = find_all_scores(p);
+            assert_eq!(
+                scores.len(),
+                1,
+                "Expected exactly one line of symmetry in this pattern:\n{}",
+                p.join("\n")
+            );
+            scores[0]
+        })
+        .sum()
 }
 
 fn main() {
     println!("{}", solve("input.txt"));
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::find_all_scores;
+
+    const EXAMPLE: [&str; 2] = [
+        "\
+#.##..##.
+..#.##.#.
+##......#
+##......#
+..#.##.#.
+..##..##.
+#.#.##.#.",
+        "\
+#...##..#
+#....#..#
+..##..###
+#####.##.
+#####.##.
+..##..###
+#....#..#",
+    ];
+
+    fn pattern(s: &str) -> Vec<String> {
+        Vec::from_iter(s.lines().map(|line| line.to_string()))
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(find_all_scores(&pattern(EXAMPLE[0])), [300]);
+        assert_eq!(find_all_scores(&pattern(EXAMPLE[1])), [100]);
+    }
+}
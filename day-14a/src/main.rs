@@ -1,7 +1,28 @@
 use core::fmt;
-use std::{collections::HashMap, fs::read_to_string, str::FromStr};
+use std::{collections::HashMap, error::Error, fs::read_to_string, str::FromStr};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{Context, Result};
+
+#[derive(PartialEq, Eq, Debug)]
+enum ParsePlatformError {
+    InvalidChar(char),
+    CoordinateOverflow(usize, usize),
+    EmptyInput,
+}
+
+impl fmt::Display for ParsePlatformError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePlatformError::InvalidChar(c) => write!(f, "Can't create a tile from {c}"),
+            ParsePlatformError::CoordinateOverflow(x, y) => {
+                write!(f, "Failed to construct coordinate from ({x}, {y})")
+            }
+            ParsePlatformError::EmptyInput => write!(f, "Can't create a platform from empty input"),
+        }
+    }
+}
+
+impl Error for ParsePlatformError {}
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 enum Tile {
@@ -11,14 +32,14 @@ enum Tile {
 }
 
 impl TryFrom<&char> for Tile {
-    type Error = anyhow::Error;
+    type Error = ParsePlatformError;
 
-    fn try_from(s: &char) -> Result<Self> {
+    fn try_from(s: &char) -> Result<Self, ParsePlatformError> {
         match s {
             'O' => Ok(Tile::RoundRock),
             '#' => Ok(Tile::CubeRock),
             '.' => Ok(Tile::Empty),
-            _ => bail!("Can't create a tile from {s}"),
+            _ => Err(ParsePlatformError::InvalidChar(*s)),
         }
     }
 }
@@ -38,12 +59,29 @@ impl fmt::Display for Tile {
 struct Coordinate(u32, u32);
 
 impl Coordinate {
-    fn from_usize_pair(x: usize, y: usize) -> Result<Self> {
+    fn from_usize_pair(x: usize, y: usize) -> Result<Self, ParsePlatformError> {
         match (x.try_into(), y.try_into()) {
             (Ok(x1), Ok(x2)) => Ok(Coordinate(x1, x2)),
-            _ => bail!("Failed to construct coordinate from ({x}, {y})"),
+            _ => Err(ParsePlatformError::CoordinateOverflow(x, y)),
         }
     }
+
+    /// The coordinates directly north, south, east and west of this one, leaving out any
+    /// that would fall outside a grid `max_x` tiles wide and `max_y` tiles tall
+    // Only the tests need this so far; it's here for grid puzzles that do
+    #[cfg(test)]
+    fn neighbors(&self, max_x: u32, max_y: u32) -> impl Iterator<Item = Coordinate> {
+        let Coordinate(x, y) = *self;
+        [
+            y.checked_sub(1).map(|y| Coordinate(x, y)),
+            y.checked_add(1).map(|y| Coordinate(x, y)),
+            x.checked_add(1).map(|x| Coordinate(x, y)),
+            x.checked_sub(1).map(|x| Coordinate(x, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&Coordinate(x, y)| x < max_x && y < max_y)
+    }
 }
 
 impl fmt::Display for Coordinate {
@@ -105,26 +143,27 @@ impl Platform {
 }
 
 impl FromStr for Platform {
-    type Err = anyhow::Error;
+    type Err = ParsePlatformError;
 
-    fn from_str(s: &str) -> Result<Self> {
+    fn from_str(s: &str) -> Result<Self, ParsePlatformError> {
         let lines: Vec<&str> = s.lines().collect();
+        if lines.is_empty() {
+            return Err(ParsePlatformError::EmptyInput);
+        }
         let mut tile_map = HashMap::new();
         for (y, row) in lines.iter().enumerate() {
             for (x, c) in row.chars().enumerate() {
-                let coordinate = Coordinate::from_usize_pair(x, y).unwrap();
-                let tile = Tile::try_from(&c).unwrap();
+                let coordinate = Coordinate::from_usize_pair(x, y)?;
+                let tile = Tile::try_from(&c)?;
                 tile_map.insert(coordinate, tile);
             }
         }
-        match (lines[0].len().try_into(), lines.len().try_into()) {
-            (Ok(max_x), Ok(max_y)) => Ok(Platform {
-                tile_map,
-                max_x,
-                max_y,
-            }),
-            _ => bail!("Couldn't parse the puzzle input :("),
-        }
+        let Coordinate(max_x, max_y) = Coordinate::from_usize_pair(lines[0].len(), lines.len())?;
+        Ok(Platform {
+            tile_map,
+            max_x,
+            max_y,
+        })
     }
 }
 
@@ -144,9 +183,10 @@ impl fmt::Display for Platform {
 }
 
 fn parse_input(filename: &str) -> Result<Platform> {
-    read_to_string(filename)
+    let platform = read_to_string(filename)
         .with_context(|| format!("Expected {filename} to exist!"))?
-        .parse()
+        .parse()?;
+    Ok(platform)
 }
 
 fn solve(filename: &str) -> u32 {
@@ -161,7 +201,7 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_input, Coordinate, Platform, Tile};
+    use crate::{parse_input, Coordinate, ParsePlatformError, Platform, Tile};
     use std::{
         collections::{HashMap, HashSet},
         fs::read_to_string,
@@ -195,6 +235,22 @@ mod tests {
         assert_eq!(platform_display, input)
     }
 
+    #[test]
+    fn test_parsing_errors() {
+        assert_eq!(
+            "O..\n.x.".parse::<Platform>().err(),
+            Some(ParsePlatformError::InvalidChar('x'))
+        );
+        assert_eq!(
+            "".parse::<Platform>().err(),
+            Some(ParsePlatformError::EmptyInput)
+        );
+        assert_eq!(
+            Coordinate::from_usize_pair(usize::MAX, 0),
+            Err(ParsePlatformError::CoordinateOverflow(usize::MAX, 0))
+        );
+    }
+
     #[test]
     fn test_tilting() {
         let mut platform = parse_input("input.txt").unwrap();
@@ -273,4 +329,55 @@ O..#.OO...
         );
         assert_eq!(platform.calculate_load(), 136)
     }
+
+    #[test]
+    fn test_neighbors() {
+        let neighbors = |x, y| HashSet::<Coordinate>::from_iter(Coordinate(x, y).neighbors(3, 4));
+        // Corners
+        assert_eq!(
+            neighbors(0, 0),
+            HashSet::from([Coordinate(1, 0), Coordinate(0, 1)])
+        );
+        assert_eq!(
+            neighbors(2, 3),
+            HashSet::from([Coordinate(1, 3), Coordinate(2, 2)])
+        );
+        // Edges
+        assert_eq!(
+            neighbors(1, 0),
+            HashSet::from([Coordinate(0, 0), Coordinate(2, 0), Coordinate(1, 1)])
+        );
+        assert_eq!(
+            neighbors(2, 1),
+            HashSet::from([Coordinate(2, 0), Coordinate(2, 2), Coordinate(1, 1)])
+        );
+        // Interior
+        assert_eq!(
+            neighbors(1, 2),
+            HashSet::from([
+                Coordinate(1, 1),
+                Coordinate(1, 3),
+                Coordinate(0, 2),
+                Coordinate(2, 2)
+            ])
+        );
+        // A grid only one tile wide
+        assert_eq!(Vec::from_iter(Coordinate(0, 0).neighbors(1, 1)), []);
+        // Only the neighbours that are inside the grid, even if this coordinate isn't
+        assert_eq!(neighbors(3, 0), HashSet::from([Coordinate(2, 0)]));
+        assert_eq!(neighbors(1, 4), HashSet::from([Coordinate(1, 3)]));
+        assert_eq!(neighbors(5, 5), HashSet::new());
+        // No overflow at the very edge of what a coordinate can hold
+        assert_eq!(
+            Vec::from_iter(Coordinate(u32::MAX, u32::MAX).neighbors(u32::MAX, u32::MAX)),
+            []
+        );
+        assert_eq!(
+            Vec::from_iter(Coordinate(u32::MAX - 1, u32::MAX - 1).neighbors(u32::MAX, u32::MAX)),
+            [
+                Coordinate(u32::MAX - 1, u32::MAX - 2),
+                Coordinate(u32::MAX - 2, u32::MAX - 1)
+            ]
+        );
+    }
 }
@@ -3,7 +3,7 @@ use std::{collections::HashMap, fs::read_to_string, str::FromStr};
 
 use anyhow::{bail, Context, Result};
 
-#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
 enum Tile {
     RoundRock,
     CubeRock,
@@ -38,11 +38,21 @@ impl fmt::Display for Tile {
 struct Coordinate(u32, u32);
 
 impl Coordinate {
-    fn from_usize_pair(x: usize, y: usize) -> Result<Self> {
-        match (x.try_into(), y.try_into()) {
-            (Ok(x1), Ok(x2)) => Ok(Coordinate(x1, x2)),
-            _ => bail!("Failed to construct coordinate from ({x}, {y})"),
-        }
+    /// The coordinates directly north, south, east and west of this one, leaving out any
+    /// that would fall outside a grid `max_x` tiles wide and `max_y` tiles tall
+    // Only the tests need this so far; it's here for grid puzzles that do
+    #[cfg(test)]
+    fn neighbors(&self, max_x: u32, max_y: u32) -> impl Iterator<Item = Coordinate> {
+        let Coordinate(x, y) = *self;
+        [
+            y.checked_sub(1).map(|y| Coordinate(x, y)),
+            y.checked_add(1).map(|y| Coordinate(x, y)),
+            x.checked_add(1).map(|x| Coordinate(x, y)),
+            x.checked_sub(1).map(|x| Coordinate(x, y)),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(move |&Coordinate(x, y)| x < max_x && y < max_y)
     }
 }
 
@@ -53,200 +63,133 @@ impl fmt::Display for Coordinate {
     }
 }
 
-type TileMap = HashMap<Coordinate, Tile>;
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+enum Direction {
+    North,
+    South,
+    West,
+    East,
+}
+
+impl TryFrom<char> for Direction {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self> {
+        match c {
+            'N' => Ok(Direction::North),
+            'S' => Ok(Direction::South),
+            'W' => Ok(Direction::West),
+            'E' => Ok(Direction::East),
+            _ => bail!("Can't create a direction from {c:?}"),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = anyhow::Error;
 
+    fn from_str(s: &str) -> Result<Self> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Direction::try_from(c),
+            _ => bail!("Expected a direction to be one of N, S, W or E, got {s:?}"),
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Direction::North => 'N',
+            Direction::South => 'S',
+            Direction::West => 'W',
+            Direction::East => 'E',
+        };
+        write!(f, "{c}")
+    }
+}
+
+/// Platforms are equal (and hash the same) when all their tiles match,
+/// which the flat row-by-row buffer makes a straight comparison
+#[derive(Clone, PartialEq, Eq, Hash)]
 struct Platform {
-    tile_map: TileMap,
+    /// Every tile on the platform, row by row
+    tiles: Vec<Tile>,
     max_x: u32,
     max_y: u32,
 }
 
 impl Platform {
-    fn tilt_north(&mut self) {
-        for x in 0..self.max_x {
-            let mut y = 0;
-            'outer_column_loop: loop {
-                if y >= (self.max_y - 1) {
-                    break;
-                }
-                let coord = Coordinate(x, y);
-                let this_tile = self.tile_map[&coord];
-                if this_tile != Tile::Empty {
-                    y += 1;
-                    continue;
-                }
-                for following_y in (y + 1)..self.max_y {
-                    let other_coord = Coordinate(x, following_y);
-                    let other_tile = self.tile_map[&other_coord];
-                    match other_tile {
-                        Tile::CubeRock => {
-                            if following_y == (self.max_y - 1) {
-                                break 'outer_column_loop;
-                            };
-                            y = following_y + 1;
-                            continue 'outer_column_loop;
-                        }
-                        Tile::RoundRock => {
-                            self.tile_map.insert(coord, Tile::RoundRock);
-                            self.tile_map.insert(other_coord, Tile::Empty);
-                            if following_y == (self.max_y - 1) {
-                                break 'outer_column_loop;
-                            };
-                            break;
-                        }
-                        Tile::Empty => {
-                            if following_y == (self.max_y - 1) {
-                                break 'outer_column_loop;
-                            };
-                            continue;
-                        }
+    fn index(&self, c: Coordinate) -> usize {
+        let Coordinate(x, y) = c;
+        (y * self.max_x + x) as usize
+    }
+
+    /// Roll every round rock as far as it'll go in `dir`.
+    ///
+    /// Each line of tiles running in that direction is swept starting from the edge
+    /// the rocks roll towards, keeping track of where the next round rock would stop.
+    fn tilt_in_direction(&mut self, dir: Direction) {
+        let (line_count, line_length) = match dir {
+            Direction::North | Direction::South => (self.max_x, self.max_y),
+            Direction::West | Direction::East => (self.max_y, self.max_x),
+        };
+        for line in 0..line_count {
+            // The `position`th tile along this line, counting from the front edge
+            let coordinate = |position: u32| match dir {
+                Direction::North => Coordinate(line, position),
+                Direction::South => Coordinate(line, line_length - 1 - position),
+                Direction::West => Coordinate(position, line),
+                Direction::East => Coordinate(line_length - 1 - position, line),
+            };
+            let mut stopping_point = 0;
+            for position in 0..line_length {
+                let i = self.index(coordinate(position));
+                match self.tiles[i] {
+                    Tile::CubeRock => stopping_point = position + 1,
+                    Tile::RoundRock => {
+                        let j = self.index(coordinate(stopping_point));
+                        self.tiles.swap(i, j);
+                        stopping_point += 1
                     }
+                    Tile::Empty => {}
                 }
-                y += 1;
             }
         }
     }
 
+    fn tilt_north(&mut self) {
+        self.tilt_in_direction(Direction::North)
+    }
+
     fn tilt_south(&mut self) {
-        for x in (0..self.max_x).rev() {
-            let mut y = self.max_y - 1;
-            'outer_column_loop: loop {
-                if y == 0 {
-                    break;
-                }
-                let coord = Coordinate(x, y);
-                let this_tile = self.tile_map[&coord];
-                if this_tile != Tile::Empty {
-                    y -= 1;
-                    continue;
-                }
-                for following_y in (0..y).rev() {
-                    let other_coord = Coordinate(x, following_y);
-                    let other_tile = self.tile_map[&other_coord];
-                    match other_tile {
-                        Tile::CubeRock => {
-                            if following_y == 0 {
-                                break 'outer_column_loop;
-                            };
-                            y = following_y - 1;
-                            continue 'outer_column_loop;
-                        }
-                        Tile::RoundRock => {
-                            self.tile_map.insert(coord, Tile::RoundRock);
-                            self.tile_map.insert(other_coord, Tile::Empty);
-                            if following_y == 0 {
-                                break 'outer_column_loop;
-                            };
-                            break;
-                        }
-                        Tile::Empty => {
-                            if following_y == 0 {
-                                break 'outer_column_loop;
-                            };
-                            continue;
-                        }
-                    }
-                }
-                y -= 1;
-            }
-        }
+        self.tilt_in_direction(Direction::South)
     }
 
     fn tilt_west(&mut self) {
-        for y in 0..self.max_y {
-            let mut x = 0;
-            'outer_column_loop: loop {
-                if x == (self.max_x - 1) {
-                    break;
-                }
-                let coord = Coordinate(x, y);
-                let this_tile = self.tile_map[&coord];
-                if this_tile != Tile::Empty {
-                    x += 1;
-                    continue;
-                }
-                for following_x in (x + 1)..self.max_x {
-                    let other_coord = Coordinate(following_x, y);
-                    let other_tile = self.tile_map[&other_coord];
-                    match other_tile {
-                        Tile::CubeRock => {
-                            if following_x == (self.max_x - 1) {
-                                break 'outer_column_loop;
-                            };
-                            x = following_x + 1;
-                            continue 'outer_column_loop;
-                        }
-                        Tile::RoundRock => {
-                            self.tile_map.insert(coord, Tile::RoundRock);
-                            self.tile_map.insert(other_coord, Tile::Empty);
-                            if following_x == (self.max_x - 1) {
-                                break 'outer_column_loop;
-                            };
-                            break;
-                        }
-                        Tile::Empty => {
-                            if following_x == (self.max_x - 1) {
-                                break 'outer_column_loop;
-                            };
-                            continue;
-                        }
-                    }
-                }
-                x += 1;
-            }
-        }
+        self.tilt_in_direction(Direction::West)
     }
 
     fn tilt_east(&mut self) {
-        for y in 0..self.max_y {
-            let mut x = self.max_x - 1;
-            'outer_column_loop: loop {
-                if x == 0 {
-                    break;
-                }
-                let coord = Coordinate(x, y);
-                let this_tile = self.tile_map[&coord];
-                if this_tile != Tile::Empty {
-                    x -= 1;
-                    continue;
-                }
-                for following_x in (0..x).rev() {
-                    let other_coord = Coordinate(following_x, y);
-                    let other_tile = self.tile_map[&other_coord];
-                    match other_tile {
-                        Tile::CubeRock => {
-                            if following_x == 0 {
-                                break 'outer_column_loop;
-                            };
-                            x = following_x - 1;
-                            continue 'outer_column_loop;
-                        }
-                        Tile::RoundRock => {
-                            self.tile_map.insert(coord, Tile::RoundRock);
-                            self.tile_map.insert(other_coord, Tile::Empty);
-                            if following_x == 0 {
-                                break 'outer_column_loop;
-                            };
-                            break;
-                        }
-                        Tile::Empty => {
-                            if following_x == 0 {
-                                break 'outer_column_loop;
-                            };
-                            continue;
-                        }
-                    }
-                }
-                x -= 1;
-            }
+        self.tilt_in_direction(Direction::East)
+    }
+
+    fn apply_tilt_sequence(&mut self, directions: &[Direction]) {
+        for &dir in directions {
+            self.tilt_in_direction(dir)
         }
     }
 
+    /// Parse a sequence of tilts written like `NWSE`, one letter per tilt
+    fn tilt_sequence_from_str(s: &str) -> Result<Vec<Direction>> {
+        s.chars().map(Direction::try_from).collect()
+    }
+
     fn cycle(&mut self) {
         self.tilt_north();
         self.tilt_west();
         self.tilt_south();
-        self.tilt_east();
+        self.tilt_east()
     }
 
     fn calculate_load(&self) -> u32 {
@@ -255,13 +198,26 @@ impl Platform {
         for x in 0..self.max_x {
             for y in 0..self.max_y {
                 let coord = Coordinate(x, y);
-                if self.tile_map[&coord] == Tile::RoundRock {
+                if self.tiles[self.index(coord)] == Tile::RoundRock {
                     answer += y_to_load_map[y as usize];
                 }
             }
         }
         answer
     }
+
+    /// How many round rocks, cube rocks and empty spaces there are on the platform
+    fn count_tiles(&self) -> (u32, u32, u32) {
+        let mut counts = (0, 0, 0);
+        for tile in &self.tiles {
+            match tile {
+                Tile::RoundRock => counts.0 += 1,
+                Tile::CubeRock => counts.1 += 1,
+                Tile::Empty => counts.2 += 1,
+            }
+        }
+        counts
+    }
 }
 
 impl FromStr for Platform {
@@ -269,17 +225,21 @@ impl FromStr for Platform {
 
     fn from_str(s: &str) -> Result<Self> {
         let lines: Vec<_> = s.lines().collect();
-        let mut tile_map = HashMap::new();
+        let Some(first_line) = lines.first() else {
+            bail!("Can't create a platform from empty input")
+        };
+        let mut tiles = Vec::with_capacity(lines.len() * first_line.len());
         for (y, row) in lines.iter().enumerate() {
-            for (x, c) in row.chars().enumerate() {
-                let coordinate = Coordinate::from_usize_pair(x, y)?;
-                let tile = Tile::try_from(&c)?;
-                tile_map.insert(coordinate, tile);
+            if row.len() != first_line.len() {
+                bail!("Expected every row to be as long as the first one, but row {y} isn't")
+            }
+            for c in row.chars() {
+                tiles.push(Tile::try_from(&c)?);
             }
         }
-        match (lines[0].len().try_into(), lines.len().try_into()) {
+        match (first_line.len().try_into(), lines.len().try_into()) {
             (Ok(max_x), Ok(max_y)) => Ok(Platform {
-                tile_map,
+                tiles,
                 max_x,
                 max_y,
             }),
@@ -294,7 +254,7 @@ impl fmt::Display for Platform {
         for y in 0..self.max_y {
             for x in 0..self.max_x {
                 let coordinate = Coordinate(x, y);
-                let tile = self.tile_map[&coordinate];
+                let tile = self.tiles[self.index(coordinate)];
                 s.push_str(&format!("{tile}"))
             }
             s.push('\n')
@@ -312,49 +272,92 @@ fn parse_input(filename: &str) -> Result<Platform> {
 // Given to us in the puzzle description
 const NUM_ITERATIONS_REQUIRED: usize = 1000000000;
 
-// Hardcoded number determined by observing the printed output of each iteration,
-// and realising that the values were cycling every 18 iterations
-const CYCLE_LENGTH: usize = 18;
-
-fn solve(filename: &str) -> u32 {
-    let mut platform = parse_input(filename).unwrap();
-    let mut previous_record = [0; CYCLE_LENGTH];
-    let mut this_record = [1; CYCLE_LENGTH];
-    let mut i = 0;
-    loop {
-        let cycle_step = i % CYCLE_LENGTH;
-        if cycle_step == 0 {
-            if this_record == previous_record {
-                break;
+/// The load on the north support beams after `cycles` spin cycles.
+///
+/// The platform soon settles into a loop, so as soon as it's in a state it's been in
+/// before, we know the loop's period and can skip all the remaining whole loops
+fn load_after_cycles(mut platform: Platform, cycles: usize) -> u32 {
+    let initial_counts = platform.count_tiles();
+    let mut seen = HashMap::<Platform, usize>::new();
+    for i in 0..cycles {
+        if let Some(previous) = seen.insert(platform.clone(), i) {
+            let period = i - previous;
+            for _ in 0..(cycles - i) % period {
+                platform.cycle();
             }
-            (previous_record, this_record) = (this_record, previous_record)
+            break;
         }
         platform.cycle();
-        let load = platform.calculate_load();
-        this_record[cycle_step] = load;
-        i += 1
-    }
-    let jumps = (NUM_ITERATIONS_REQUIRED - i) % CYCLE_LENGTH;
-    for _ in 0..jumps {
-        platform.cycle();
     }
+    debug_assert_eq!(platform.count_tiles(), initial_counts);
     platform.calculate_load()
 }
 
+/// The load on the north support beams after tilting the platform once in each
+/// of the directions in `tilts`, printing the platform as it's left to stderr
+fn load_after_tilts(mut platform: Platform, tilts: &[Direction]) -> u32 {
+    platform.apply_tilt_sequence(tilts);
+    eprintln!("{platform}");
+    platform.calculate_load()
+}
+
+#[derive(Default)]
+struct Options {
+    /// Tilt the platform in this sequence, e.g. `NWSE`, instead of spinning it
+    tilts: Option<Vec<Direction>>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--tilts" => {
+                let Some(sequence) = args.next() else {
+                    bail!("Expected a sequence of tilts like `NWSE` after `--tilts`")
+                };
+                options.tilts = Some(Platform::tilt_sequence_from_str(&sequence)?)
+            }
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
+}
+
+fn solve(filename: &str, options: &Options) -> u32 {
+    let platform = parse_input(filename).unwrap();
+    match &options.tilts {
+        Some(tilts) => load_after_tilts(platform, tilts),
+        None => load_after_cycles(platform, NUM_ITERATIONS_REQUIRED),
+    }
+}
+
 fn main() {
-    println!("{}", solve("input.txt"))
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    println!("{}", solve("input.txt", &options))
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{parse_input, Coordinate, Platform, Tile};
+    use crate::{
+        load_after_cycles, load_after_tilts, parse_args, parse_input, Coordinate, Direction,
+        Platform, Tile, NUM_ITERATIONS_REQUIRED,
+    };
     use std::{
-        collections::{HashMap, HashSet},
+        collections::{hash_map::DefaultHasher, HashSet},
         fs::read_to_string,
+        hash::{Hash, Hasher},
     };
 
     const FILENAME: &str = "input.txt";
 
+    /// The order the platform gets tilted in during each spin cycle
+    const SPIN_CYCLE: [Direction; 4] = [
+        Direction::North,
+        Direction::West,
+        Direction::South,
+        Direction::East,
+    ];
+
     fn create_platform() -> Platform {
         parse_input(FILENAME).unwrap()
     }
@@ -362,16 +365,27 @@ mod tests {
     #[test]
     fn test_parsing_basics() {
         let platform = create_platform();
-        assert_eq!(platform.tile_map.len(), 10_000);
+        assert_eq!(platform.tiles.len(), 10_000);
         assert_eq!(platform.max_x, 100);
         assert_eq!(platform.max_y, 100);
 
+        // Every coordinate has its own slot in the flat buffer
+        let mut indices = HashSet::new();
         for x in 0..platform.max_x {
             for y in 0..platform.max_y {
-                let coordinate = Coordinate(x, y);
-                assert!(platform.tile_map.contains_key(&coordinate))
+                let index = platform.index(Coordinate(x, y));
+                assert!(index < platform.tiles.len());
+                assert!(indices.insert(index))
             }
         }
+        assert_eq!(platform.index(Coordinate(3, 0)), 3);
+        assert_eq!(platform.index(Coordinate(3, 2)), 203);
+    }
+
+    #[test]
+    fn test_parsing_ragged_rows() {
+        assert!("O..\n#.".parse::<Platform>().is_err());
+        assert!("".parse::<Platform>().is_err())
     }
 
     #[test]
@@ -389,25 +403,40 @@ mod tests {
     #[test]
     fn test_tilting_basics() {
         let mut platform = create_platform();
-        let tiles: HashMap<Coordinate, Tile> = platform
-            .tile_map
-            .iter()
-            .map(|(k, v)| (k.to_owned(), v.to_owned()))
-            .collect();
-        assert_eq!(platform.tile_map, tiles);
+        let tiles: Vec<Tile> = platform.tiles.clone();
+        assert_eq!(platform.tiles, tiles);
 
         platform.tilt_north();
-        assert_ne!(platform.tile_map, tiles);
-        assert_eq!(platform.tile_map.len(), 10_000);
+        assert_ne!(platform.tiles, tiles);
+        assert_eq!(platform.tiles.len(), 10_000);
         assert_eq!(platform.max_x, 100);
         assert_eq!(platform.max_y, 100);
+    }
 
-        for x in 0..platform.max_x {
-            for y in 0..platform.max_y {
-                let coordinate = Coordinate(x, y);
-                assert!(platform.tile_map.contains_key(&coordinate))
-            }
+    #[test]
+    fn test_tilting_conserves_rocks() {
+        let mut platform = create_platform();
+        let counts = platform.count_tiles();
+        assert_eq!(counts.0 + counts.1 + counts.2, 10_000);
+        // Tilting moves round rocks around, but never creates or destroys any rocks
+        let tilts = [
+            Direction::North,
+            Direction::East,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+            Direction::North,
+            Direction::South,
+        ];
+        for dir in tilts {
+            platform.tilt_in_direction(dir);
+            assert_eq!(platform.count_tiles(), counts, "{dir:?}");
         }
+        platform.cycle();
+        assert_eq!(platform.count_tiles(), counts);
+
+        let small: Platform = "O.#\n.O.\n#..".parse().unwrap();
+        assert_eq!(small.count_tiles(), (2, 2, 5));
     }
 
     #[test]
@@ -439,6 +468,42 @@ mod tests {
         assert_eq!(platform_display_6, platform_display_8);
     }
 
+    #[test]
+    fn test_tilt_in_direction() {
+        let cases = [
+            (Direction::North, Platform::tilt_north as fn(&mut Platform)),
+            (Direction::South, Platform::tilt_south),
+            (Direction::West, Platform::tilt_west),
+            (Direction::East, Platform::tilt_east),
+        ];
+        for (dir, tilt) in cases {
+            let mut expected = create_platform();
+            tilt(&mut expected);
+            let mut platform = create_platform();
+            platform.tilt_in_direction(dir);
+            assert_eq!(platform.tiles, expected.tiles, "{dir:?}");
+        }
+    }
+
+    #[test]
+    fn test_tilt_each_way() {
+        let input = "\
+.O#O.
+O..#O
+#O..O";
+        let cases = [
+            (Direction::North, "OO#OO\n.O.#O\n#...."),
+            (Direction::South, "..#O.\nOO.#O\n#O..O"),
+            (Direction::West, "O.#O.\nO..#O\n#OO.."),
+            (Direction::East, ".O#.O\n..O#O\n#..OO"),
+        ];
+        for (dir, expected) in cases {
+            let mut platform: Platform = input.parse().unwrap();
+            platform.tilt_in_direction(dir);
+            assert_eq!(platform.to_string(), expected, "{dir:?}");
+        }
+    }
+
     #[test]
     fn test_cycle_basics() {
         let mut platform = create_platform();
@@ -449,6 +514,34 @@ mod tests {
         assert_eq!(platform_display, platform_display_2)
     }
 
+    #[test]
+    fn test_platform_equality_and_hashing() {
+        let hash = |platform: &Platform| {
+            let mut hasher = DefaultHasher::new();
+            platform.hash(&mut hasher);
+            hasher.finish()
+        };
+        let mut platform = create_platform();
+        let copy = create_platform();
+        assert!(platform == copy);
+        assert_eq!(hash(&platform), hash(&copy));
+
+        platform.tilt_north();
+        assert!(platform != copy);
+        assert_ne!(hash(&platform), hash(&copy));
+
+        // Tilting north twice is the same as tilting north once
+        let tilted = platform.clone();
+        platform.tilt_north();
+        assert!(platform == tilted);
+        assert_eq!(hash(&platform), hash(&tilted));
+
+        let mut seen = HashSet::new();
+        assert!(seen.insert(copy));
+        assert!(seen.insert(tilted));
+        assert!(!seen.insert(platform));
+    }
+
     #[test]
     fn test_coordinate() {
         let coord = Coordinate(0, 0);
@@ -565,4 +658,169 @@ O.#..O.#.#
         let cycled_platform_display_3 = platform.to_string();
         assert_eq!(cycled_input_3, cycled_platform_display_3.as_str());
     }
+
+    #[test]
+    fn test_load_after_cycles() {
+        let input = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+        let platform = || input.parse::<Platform>().unwrap();
+        assert_eq!(load_after_cycles(platform(), NUM_ITERATIONS_REQUIRED), 64);
+
+        // Short runs that finish before (or just as) the loop is spotted must agree with
+        // cycling the platform the slow way
+        for cycles in 0..30 {
+            let mut expected = platform();
+            for _ in 0..cycles {
+                expected.cycle();
+            }
+            assert_eq!(
+                load_after_cycles(platform(), cycles),
+                expected.calculate_load(),
+                "{cycles}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_direction_parsing_and_display() {
+        for dir in [
+            Direction::North,
+            Direction::South,
+            Direction::West,
+            Direction::East,
+        ] {
+            assert_eq!(dir.to_string().parse::<Direction>().unwrap(), dir);
+        }
+        assert_eq!("W".parse::<Direction>().unwrap(), Direction::West);
+        assert!("n".parse::<Direction>().is_err());
+        assert!("NS".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+
+        assert_eq!(
+            Platform::tilt_sequence_from_str("NWSE").unwrap(),
+            SPIN_CYCLE
+        );
+        assert_eq!(Platform::tilt_sequence_from_str("").unwrap(), []);
+        assert!(Platform::tilt_sequence_from_str("NWSX").is_err());
+    }
+
+    #[test]
+    fn test_tilt_sequences() {
+        let after = |sequence: &str| {
+            let mut platform = create_platform();
+            platform.apply_tilt_sequence(&Platform::tilt_sequence_from_str(sequence).unwrap());
+            platform.to_string()
+        };
+        // Tilting the same way again changes nothing
+        assert_eq!(after("NNNN"), after("N"));
+        assert_eq!(after("WEE"), after("WE"));
+        // Once the rocks have been tilted one way and back again, every later
+        // back-and-forth puts them in the same places
+        for sequence in ["NS", "SN", "WE", "EW"] {
+            assert_eq!(after(&sequence.repeat(2)), after(sequence), "{sequence}");
+            assert_eq!(after(&sequence.repeat(3)), after(sequence), "{sequence}");
+        }
+        // A spin cycle is just the four tilts in order
+        let mut platform = create_platform();
+        platform.cycle();
+        assert_eq!(platform.to_string(), after("NWSE"));
+    }
+
+    #[test]
+    fn test_load_after_tilts() {
+        let input = "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+        let platform = || input.parse::<Platform>().unwrap();
+        // Tilting north once is part a
+        assert_eq!(load_after_tilts(platform(), &[Direction::North]), 136);
+        // The example gives the load after one spin cycle as 87
+        assert_eq!(load_after_tilts(platform(), &SPIN_CYCLE), 87);
+        assert_eq!(
+            load_after_tilts(platform(), &[]),
+            platform().calculate_load()
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(parse(&[]).unwrap().tilts, None);
+        assert_eq!(
+            parse(&["--tilts", "NWSE"]).unwrap().tilts,
+            Some(SPIN_CYCLE.to_vec())
+        );
+        assert_eq!(parse(&["--tilts", ""]).unwrap().tilts, Some(vec![]));
+        assert!(parse(&["--tilts"]).is_err());
+        assert!(parse(&["--tilts", "NWSX"]).is_err());
+        assert!(parse(&["--spin"]).is_err());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let neighbors = |x, y| HashSet::<Coordinate>::from_iter(Coordinate(x, y).neighbors(3, 4));
+        // Corners
+        assert_eq!(
+            neighbors(0, 0),
+            HashSet::from([Coordinate(1, 0), Coordinate(0, 1)])
+        );
+        assert_eq!(
+            neighbors(2, 3),
+            HashSet::from([Coordinate(1, 3), Coordinate(2, 2)])
+        );
+        // Edges
+        assert_eq!(
+            neighbors(1, 0),
+            HashSet::from([Coordinate(0, 0), Coordinate(2, 0), Coordinate(1, 1)])
+        );
+        assert_eq!(
+            neighbors(2, 1),
+            HashSet::from([Coordinate(2, 0), Coordinate(2, 2), Coordinate(1, 1)])
+        );
+        // Interior
+        assert_eq!(
+            neighbors(1, 2),
+            HashSet::from([
+                Coordinate(1, 1),
+                Coordinate(1, 3),
+                Coordinate(0, 2),
+                Coordinate(2, 2)
+            ])
+        );
+        // A grid only one tile wide
+        assert_eq!(Vec::from_iter(Coordinate(0, 0).neighbors(1, 1)), []);
+        // Only the neighbours that are inside the grid, even if this coordinate isn't
+        assert_eq!(neighbors(3, 0), HashSet::from([Coordinate(2, 0)]));
+        assert_eq!(neighbors(1, 4), HashSet::from([Coordinate(1, 3)]));
+        assert_eq!(neighbors(5, 5), HashSet::new());
+        // No overflow at the very edge of what a coordinate can hold
+        assert_eq!(
+            Vec::from_iter(Coordinate(u32::MAX, u32::MAX).neighbors(u32::MAX, u32::MAX)),
+            []
+        );
+        assert_eq!(
+            Vec::from_iter(Coordinate(u32::MAX - 1, u32::MAX - 1).neighbors(u32::MAX, u32::MAX)),
+            [
+                Coordinate(u32::MAX - 1, u32::MAX - 2),
+                Coordinate(u32::MAX - 2, u32::MAX - 1)
+            ]
+        );
+    }
 }
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::hint::black_box;
+use std::sync::{LazyLock, Mutex};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use day_15b::box_number_from_label;
+
+/// What `#[cached]` used to do: clone the label, then look it up in a global cache behind a lock
+fn box_number_from_cloned_label(label: String) -> u8 {
+    static CACHE: LazyLock<Mutex<HashMap<String, u8>>> = LazyLock::new(Default::default);
+    let mut cache = CACHE.lock().unwrap();
+    if let Some(&box_number) = cache.get(&label) {
+        return box_number;
+    }
+    let box_number = box_number_from_label(&label);
+    cache.insert(label, box_number);
+    box_number
+}
+
+fn labels() -> Vec<String> {
+    let input = read_to_string("input.txt").expect("Expected input.txt to exist!");
+    input
+        .trim()
+        .split(',')
+        .map(|step| step.trim_end_matches(|c: char| c == '-' || c == '=' || c.is_ascii_digit()))
+        .map(str::to_string)
+        .collect()
+}
+
+fn bench_box_numbers(c: &mut Criterion) {
+    let labels = labels();
+    let mut group = c.benchmark_group("box numbers for every step of the input");
+    group.bench_function("cloning the label", |b| {
+        b.iter(|| {
+            for label in &labels {
+                black_box(box_number_from_cloned_label(black_box(label).to_string()));
+            }
+        })
+    });
+    group.bench_function("borrowing the label", |b| {
+        b.iter(|| {
+            for label in &labels {
+                black_box(box_number_from_label(black_box(label)));
+            }
+        })
+    });
+    group.finish()
+}
+
+criterion_group!(benches, bench_box_numbers);
+criterion_main!(benches);
@@ -0,0 +1,47 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+pub type Label = String;
+
+thread_local! {
+    static BOX_NUMBERS: RefCell<HashMap<Label, u8>> = RefCell::new(HashMap::new());
+}
+
+/// Run the HASH algorithm on `label`.
+/// Results are cached per thread, and the label is only copied the first time it's seen.
+pub fn box_number_from_label(label: &str) -> u8 {
+    debug_assert!(label.is_ascii());
+    BOX_NUMBERS.with_borrow_mut(|cache| {
+        if let Some(&box_number) = cache.get(label) {
+            return box_number;
+        }
+        let mut answer: u32 = 0;
+        for byte in label.bytes() {
+            answer += byte as u32;
+            answer *= 17;
+            answer %= 256
+        }
+        let box_number = answer.try_into().expect("Expected result to be <256!");
+        cache.insert(label.to_owned(), box_number);
+        box_number
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::box_number_from_label;
+
+    #[test]
+    fn test_box_number_from_label() {
+        assert_eq!(box_number_from_label("HASH"), 52);
+        assert_eq!(box_number_from_label("rn"), 0);
+        assert_eq!(box_number_from_label("cm"), 0);
+        assert_eq!(box_number_from_label("qp"), 1);
+        assert_eq!(box_number_from_label("pc"), 3);
+        assert_eq!(box_number_from_label("ot"), 3);
+        assert_eq!(box_number_from_label("ab"), 3);
+        // Asking again gives the cached answer
+        assert_eq!(box_number_from_label("HASH"), 52);
+        assert_eq!(box_number_from_label(""), 0);
+    }
+}
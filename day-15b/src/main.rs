@@ -1,22 +1,9 @@
 use std::collections::HashMap;
+use std::fmt;
 use std::{fs::read_to_string, str::FromStr};
 
 use anyhow::{bail, Ok, Result};
-use cached::proc_macro::cached;
-
-type Label = String;
-
-#[cached]
-fn box_number_from_label(label: Label) -> u8 {
-    debug_assert!(label.is_ascii());
-    let mut answer: u32 = 0;
-    for byte in label.bytes() {
-        answer += byte as u32;
-        answer *= 17;
-        answer %= 256
-    }
-    answer.try_into().expect("Expected result to be <256!")
-}
+use day_15b::{box_number_from_label, Label};
 
 #[derive(PartialEq, Eq, Debug)]
 enum Operation {
@@ -25,12 +12,15 @@ enum Operation {
 }
 
 impl Operation {
-    fn box_number(&self) -> u8 {
-        let label = match self {
+    fn label(&self) -> &str {
+        match self {
             Operation::RemoveLens(label) => label,
             Operation::InsertLens(label, _) => label,
-        };
-        box_number_from_label(label.to_string())
+        }
+    }
+
+    fn box_number(&self) -> u8 {
+        box_number_from_label(self.label())
     }
 }
 
@@ -54,6 +44,12 @@ struct Lens {
     focal_length: u8,
 }
 
+impl fmt::Display for Lens {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.focal_length)
+    }
+}
+
 #[derive(PartialEq, Eq, Debug)]
 struct Box {
     index_to_label: Vec<Label>,
@@ -112,12 +108,23 @@ impl Box {
             .collect()
     }
 
-    #[cfg(test)]
     fn is_empty(&self) -> bool {
         self.index_to_label.is_empty()
     }
 }
 
+/// The lenses in the box, in order, as the puzzle writes them: `[rn 1] [cm 2]`
+impl fmt::Display for Box {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lenses = Vec::from_iter(
+            self.index_to_label
+                .iter()
+                .map(|label| format!("[{label} {}]", self.label_to_lens[label])),
+        );
+        write!(f, "{}", lenses.join(" "))
+    }
+}
+
 struct BoxArray {
     boxes: [Box; 256],
 }
@@ -152,6 +159,20 @@ impl BoxArray {
     }
 }
 
+/// One line per box with any lenses in it, as the puzzle writes them: `Box 0: [rn 1] [cm 2]`
+impl fmt::Display for BoxArray {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines = Vec::from_iter(
+            self.boxes
+                .iter()
+                .enumerate()
+                .filter(|(_, b)| !b.is_empty())
+                .map(|(i, b)| format!("Box {i}: {b}")),
+        );
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
 fn parse_input(input: &str) -> Result<Vec<Operation>> {
     input.split(',').map(|s| s.parse()).collect()
 }
@@ -214,6 +235,17 @@ mod tests {
         Operation::from_str(input).unwrap()
     }
 
+    #[test]
+    fn test_operation_labels() {
+        assert_eq!(operation("rn=1").label(), "rn");
+        assert_eq!(operation("cm-").label(), "cm");
+        assert_eq!(operation("qp=3").box_number(), 1);
+        assert_eq!(operation("pc-").box_number(), 3);
+        // Labels aren't limited to two letters
+        assert_eq!(operation("abcdef=9").label(), "abcdef");
+        assert_eq!(operation("HASH-").label(), "HASH");
+    }
+
     fn lens_vec(data: &[(&str, u8)]) -> Vec<(String, Lens)> {
         data.iter()
             .map(|(k, v)| (k.to_string(), Lens { focal_length: *v }))
@@ -317,4 +349,25 @@ mod tests {
             lens_vec(&[("ot", 7), ("ab", 5), ("pc", 6)])
         );
     }
+
+    #[test]
+    fn test_display() {
+        let mut box_array = BoxArray::new();
+        assert_eq!(box_array.to_string(), "");
+        let steps = parse_input("rn=1,cm-,qp=3,cm=2,qp-,pc=4").unwrap();
+        for step in steps {
+            box_array.apply_operation(step)
+        }
+        assert_eq!(box_array.to_string(), "Box 0: [rn 1] [cm 2]\nBox 3: [pc 4]");
+        let steps = parse_input("ot=9,ab=5,pc-,pc=6,ot=7").unwrap();
+        for step in steps {
+            box_array.apply_operation(step)
+        }
+        assert_eq!(
+            box_array.to_string(),
+            "Box 0: [rn 1] [cm 2]\nBox 3: [ot 7] [ab 5] [pc 6]"
+        );
+        assert_eq!(box_array.boxes[3].to_string(), "[ot 7] [ab 5] [pc 6]");
+        assert_eq!(Lens { focal_length: 7 }.to_string(), "7");
+    }
 }
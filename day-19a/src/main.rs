@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::fmt::Display;
 use std::fs::read_to_string;
+use std::ops::Range;
 use std::str::FromStr;
 
 use anyhow::{bail, Context, Error, Result};
@@ -56,6 +57,53 @@ impl FromStr for Part {
     }
 }
 
+/// A hypercube of parts, with a half-open range of ratings for each attribute
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PartRange {
+    x: Range<u32>,
+    m: Range<u32>,
+    a: Range<u32>,
+    s: Range<u32>,
+}
+
+impl PartRange {
+    fn all() -> Self {
+        PartRange {
+            x: 1..4001,
+            m: 1..4001,
+            a: 1..4001,
+            s: 1..4001,
+        }
+    }
+
+    fn volume(&self) -> u128 {
+        [&self.x, &self.m, &self.a, &self.s]
+            .iter()
+            .map(|r| r.len() as u128)
+            .product()
+    }
+
+    fn with_attr(&self, attr: Attr, range: Range<u32>) -> Self {
+        let mut new = self.clone();
+        match attr {
+            Attr::X => new.x = range,
+            Attr::M => new.m = range,
+            Attr::A => new.a = range,
+            Attr::S => new.s = range,
+        }
+        new
+    }
+
+    fn attr(&self, attr: Attr) -> &Range<u32> {
+        match attr {
+            Attr::X => &self.x,
+            Attr::M => &self.m,
+            Attr::A => &self.a,
+            Attr::S => &self.s,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum Compare {
     Lt,
@@ -154,6 +202,26 @@ impl Rule {
             None
         }
     }
+
+    /// Split `range` into the parts that this rule matches and the parts it doesn't
+    fn split(&self, range: &PartRange) -> (PartRange, PartRange) {
+        let Some(attr) = self.attr else {
+            return (range.clone(), range.with_attr(Attr::X, 0..0));
+        };
+        let Range { start, end } = range.attr(attr).clone();
+        let (matched, unmatched) = match self.cmp {
+            Compare::Lt => (start..end.min(self.value), start.max(self.value)..end),
+            Compare::Gt => (
+                start.max(self.value + 1)..end,
+                start..end.min(self.value + 1),
+            ),
+            Compare::NoOp => unreachable!("Only no-op rules should have no attribute!"),
+        };
+        (
+            range.with_attr(attr, matched),
+            range.with_attr(attr, unmatched),
+        )
+    }
 }
 
 impl FromStr for Rule {
@@ -214,12 +282,34 @@ impl Workflow {
         }
         unreachable!("At least one rule in self.rules should have returned a `Decision` variant!")
     }
+
+    fn num_rules(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// The number of parts in `range` that one of this workflow's rules decides
+    /// what to do with, whether that's accepting them, rejecting them or sending them on.
+    /// That's all of them unless the last rule has a condition.
+    fn max_parts_in_range(&self, range: &PartRange) -> u128 {
+        let mut remaining = range.clone();
+        let mut total = 0;
+        for rule in &self.rules {
+            let (matched, unmatched) = rule.split(&remaining);
+            total += matched.volume();
+            remaining = unmatched;
+        }
+        total
+    }
 }
 
 impl Display for Workflow {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Workflow { name, rules } = self;
-        write!(f, "Workflow(\"{name}\", <{} rules>)", rules.len())
+        write!(
+            f,
+            "Workflow(\"{}\", <{} rules>)",
+            self.name,
+            self.num_rules()
+        )
     }
 }
 
@@ -242,7 +332,14 @@ impl FromStr for PuzzleInput {
             .map(|line| line.parse())
             .collect::<Result<Vec<Workflow>>>()?;
         let mut workflow_map = HashMap::new();
+        let all_parts = PartRange::all();
         for workflow in workflows {
+            if workflow.max_parts_in_range(&all_parts) != all_parts.volume() {
+                bail!(
+                    "Workflow {} doesn't decide what to do with every part",
+                    workflow.name
+                )
+            }
             workflow_map.insert(workflow.name.to_owned(), workflow);
         }
         let parts = part_strings
@@ -284,3 +381,89 @@ fn solve(filename: &str) -> u32 {
 fn main() {
     println!("{}", solve("input.txt"));
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{PartRange, PuzzleInput, Workflow};
+
+    const EXAMPLE_WORKFLOWS: &str = "\
+px{a<2006:qkq,m>2090:A,rfg}
+pv{a>1716:R,A}
+lnx{m>1548:A,A}
+rfg{s<537:gd,x>2440:R,A}
+qs{s>3448:A,lnx}
+qkq{x<1416:A,crn}
+crn{x>2662:A,R}
+in{s<1351:px,qqz}
+qqz{s>2770:qs,m<1801:hdj,R}
+gd{a>3333:R,R}
+hdj{m>838:A,pv}";
+
+    #[test]
+    fn test_num_rules() {
+        let workflow = Workflow::from_str("px{a<2006:qkq,m>2090:A,rfg}").unwrap();
+        assert_eq!(workflow.num_rules(), 3);
+        let workflow = Workflow::from_str("in{s<1351:px,qqz}").unwrap();
+        assert_eq!(workflow.num_rules(), 2);
+    }
+
+    #[test]
+    fn test_max_parts_in_range_on_the_example() {
+        let all = PartRange::all();
+        let narrow = PartRange {
+            x: 1..3,
+            m: 1..3,
+            a: 1..3,
+            s: 1..11,
+        };
+        for line in EXAMPLE_WORKFLOWS.lines() {
+            // Every example workflow ends with a rule that catches whatever's left,
+            // including the ones that reject everything
+            let workflow = Workflow::from_str(line).unwrap();
+            assert_eq!(workflow.max_parts_in_range(&all), all.volume(), "{line}");
+            assert_eq!(
+                workflow.max_parts_in_range(&narrow),
+                narrow.volume(),
+                "{line}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_max_parts_in_range_without_a_fallback() {
+        // Only parts with x > 1000 are dealt with
+        let workflow = Workflow::from_str("ab{x>1000:R}").unwrap();
+        assert_eq!(
+            workflow.max_parts_in_range(&PartRange::all()),
+            3000 * 4000 * 4000 * 4000
+        );
+
+        // Parts with m < 11, and then any others with x > 5
+        let workflow = Workflow::from_str("cd{m<11:A,x>5:R}").unwrap();
+        assert_eq!(
+            workflow.max_parts_in_range(&PartRange::all()),
+            4000 * 10 * 4000 * 4000 + 3995 * 3990 * 4000 * 4000
+        );
+        let range = PartRange {
+            x: 1..11,
+            m: 5..15,
+            a: 1..2,
+            s: 1..2,
+        };
+        // m = 5..=10 always, plus x = 6..=10 for m = 11..=14
+        assert_eq!(workflow.max_parts_in_range(&range), 10 * 6 + 5 * 4);
+    }
+
+    #[test]
+    fn test_parsing_rejects_workflows_without_a_fallback() {
+        let parts = "{x=787,m=2655,a=1222,s=2876}";
+        assert!(PuzzleInput::from_str(&format!("{EXAMPLE_WORKFLOWS}\n\n{parts}")).is_ok());
+        let error =
+            PuzzleInput::from_str(&format!("{EXAMPLE_WORKFLOWS}\nab{{x>1000:R}}\n\n{parts}"))
+                .err()
+                .unwrap();
+        assert!(error.to_string().contains("Workflow ab"), "{error}");
+    }
+}
@@ -0,0 +1,775 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::iter::Sum;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PulseKind {
+    High,
+    Low,
+}
+
+impl Display for PulseKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PulseKind::High => write!(f, "high"),
+            PulseKind::Low => write!(f, "low"),
+        }
+    }
+}
+
+/// A module's index in the `Network`
+pub type ModuleId = u16;
+
+/// A single pulse being delivered from one module to another
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PulseEvent {
+    /// `None` if the pulse was sent by the button
+    pub sender: Option<ModuleId>,
+    pub receiver: ModuleId,
+    pub kind: PulseKind,
+}
+
+/// A snapshot of the mutable state held by a single module
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ModuleState {
+    FlipFlop(bool),
+    Conjunction(Vec<PulseKind>),
+    Stateless,
+}
+
+/// A snapshot of the mutable state of every module in a `Network`
+pub type StateKey = Vec<ModuleState>;
+
+#[derive(Debug)]
+pub enum Module {
+    FlipFlop {
+        connections: Vec<ModuleId>,
+        is_on: bool,
+    },
+    Conjunction {
+        connections: Vec<ModuleId>,
+        memory: Vec<(ModuleId, PulseKind)>,
+    },
+    Broadcast {
+        connections: Vec<ModuleId>,
+    },
+    Untyped,
+}
+
+impl Module {
+    pub fn connections(&self) -> &[ModuleId] {
+        match self {
+            Module::FlipFlop { connections, .. } => connections,
+            Module::Conjunction { connections, .. } => connections,
+            Module::Broadcast { connections } => connections,
+            Module::Untyped => &[],
+        }
+    }
+
+    pub fn is_conjunction(&self) -> bool {
+        matches!(self, Module::Conjunction { .. })
+    }
+
+    pub fn state(&self) -> ModuleState {
+        match self {
+            Module::FlipFlop { is_on, .. } => ModuleState::FlipFlop(*is_on),
+            Module::Conjunction { memory, .. } => {
+                ModuleState::Conjunction(Vec::from_iter(memory.iter().map(|(_, kind)| *kind)))
+            }
+            Module::Broadcast { .. } | Module::Untyped => ModuleState::Stateless,
+        }
+    }
+
+    /// Return the module to the state it was in before any buttons were pressed
+    pub fn reset(&mut self) {
+        match self {
+            Module::FlipFlop { is_on, .. } => *is_on = false,
+            Module::Conjunction { memory, .. } => {
+                for (_, kind) in memory.iter_mut() {
+                    *kind = PulseKind::Low
+                }
+            }
+            Module::Broadcast { .. } | Module::Untyped => {}
+        }
+    }
+
+    /// Returns the kind of pulse this module sends in response, if any
+    fn receive_pulse(&mut self, kind: PulseKind, from_: Option<ModuleId>) -> Option<PulseKind> {
+        match self {
+            Module::FlipFlop { is_on, .. } => match (*is_on, kind) {
+                (_, PulseKind::High) => None,
+                (true, PulseKind::Low) => {
+                    *is_on = false;
+                    Some(PulseKind::Low)
+                }
+                (false, PulseKind::Low) => {
+                    *is_on = true;
+                    Some(PulseKind::High)
+                }
+            },
+            Module::Conjunction { memory, .. } => {
+                let remembered = memory
+                    .iter_mut()
+                    .find(|(input, _)| Some(*input) == from_)
+                    .map(|(_, remembered)| remembered);
+                debug_assert!(remembered.is_some());
+                if let Some(remembered) = remembered {
+                    *remembered = kind
+                }
+                if memory.iter().all(|(_, k)| k == &PulseKind::High) {
+                    Some(PulseKind::Low)
+                } else {
+                    Some(PulseKind::High)
+                }
+            }
+            Module::Broadcast { .. } => Some(kind),
+            Module::Untyped => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Network {
+    modules: Vec<Module>,
+    names: Vec<String>,
+    indices: HashMap<String, ModuleId>,
+}
+
+impl Network {
+    pub fn index_of(&self, name: &str) -> Option<ModuleId> {
+        self.indices.get(name).copied()
+    }
+
+    pub fn name_of(&self, id: ModuleId) -> &str {
+        &self.names[id as usize]
+    }
+
+    pub fn modules(&self) -> impl Iterator<Item = (ModuleId, &Module)> {
+        (0..).zip(self.modules.iter())
+    }
+
+    pub fn state(&self) -> StateKey {
+        Vec::from_iter(self.modules.iter().map(|m| m.state()))
+    }
+
+    /// Return every module to the state it was in before any buttons were pressed,
+    /// so the network can be run again from the start
+    pub fn reset(&mut self) {
+        for module in self.modules.iter_mut() {
+            module.reset()
+        }
+    }
+
+    /// Describe a module's name, kind and current state, e.g. `%a: on`
+    /// or `&con: a=high, b=low`
+    pub fn describe_module(&self, id: ModuleId) -> String {
+        let name = self.name_of(id);
+        match &self.modules[id as usize] {
+            Module::FlipFlop { is_on, .. } => {
+                format!("%{name}: {}", if *is_on { "on" } else { "off" })
+            }
+            Module::Conjunction { memory, .. } => {
+                let memory = Vec::from_iter(
+                    memory
+                        .iter()
+                        .map(|(input, kind)| format!("{}={kind}", self.name_of(*input))),
+                );
+                format!("&{name}: {}", memory.join(", "))
+            }
+            Module::Broadcast { .. } => format!("{name} (broadcast)"),
+            Module::Untyped => format!("{name} (untyped)"),
+        }
+    }
+
+    /// Describe every module in the network, one per line
+    pub fn dump(&self) -> String {
+        let lines = Vec::from_iter(self.modules().map(|(id, _)| self.describe_module(id)));
+        lines.join("\n")
+    }
+
+    /// Describe the event in the same format the puzzle uses,
+    /// e.g. `broadcaster -low-> a`
+    pub fn describe_event(&self, event: &PulseEvent) -> String {
+        let sender = event.sender.map_or("button", |id| self.name_of(id));
+        format!(
+            "{sender} -{}-> {}",
+            event.kind,
+            self.name_of(event.receiver)
+        )
+    }
+
+    /// Push the button once, calling `observer` with every pulse
+    /// delivered during the press, in the order they're delivered
+    pub fn press_button(
+        &mut self,
+        mut observer: impl FnMut(&PulseEvent),
+    ) -> Result<PulseStatistics> {
+        let broadcaster = self.index_of("broadcaster").context(
+            "The button sends pulses to `broadcaster`, but it isn't defined in the network",
+        )?;
+        let mut statistics = PulseStatistics::new();
+        // Every pulse gets its own entry in the queue, so pulses are delivered in
+        // exactly the order they're sent. The button sends the first one.
+        let mut queue = VecDeque::from([PulseEvent {
+            sender: None,
+            receiver: broadcaster,
+            kind: PulseKind::Low,
+        }]);
+        while let Some(event) = queue.pop_front() {
+            statistics.update(&event.kind);
+            observer(&event);
+            let receiver = &mut self.modules[event.receiver as usize];
+            if let Some(kind) = receiver.receive_pulse(event.kind, event.sender) {
+                for &conn in receiver.connections() {
+                    queue.push_back(PulseEvent {
+                        sender: Some(event.receiver),
+                        receiver: conn,
+                        kind,
+                    })
+                }
+            }
+        }
+        Ok(statistics)
+    }
+
+    /// Keep pressing the button until `predicate` returns `true` for a pulse,
+    /// returning the number of the press on which that happened.
+    /// Returns `None` if it didn't happen within `max_presses` presses.
+    pub fn press_until(
+        &mut self,
+        max_presses: u64,
+        mut predicate: impl FnMut(&PulseEvent) -> bool,
+    ) -> Result<Option<u64>> {
+        for press in 1..=max_presses {
+            let mut fired = false;
+            self.press_button(|event| fired |= predicate(event))
+                .with_context(|| format!("Failed on button press {press}"))?;
+            if fired {
+                return Ok(Some(press));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PulseStatistics {
+    high_pulses_sent: u64,
+    low_pulses_sent: u64,
+}
+
+impl PulseStatistics {
+    fn new() -> Self {
+        Self {
+            high_pulses_sent: 0,
+            low_pulses_sent: 0,
+        }
+    }
+
+    fn update(&mut self, kind: &PulseKind) {
+        match kind {
+            PulseKind::High => self.high_pulses_sent += 1,
+            PulseKind::Low => self.low_pulses_sent += 1,
+        }
+    }
+
+    pub fn multiply(&self) -> u64 {
+        self.high_pulses_sent * self.low_pulses_sent
+    }
+}
+
+impl Add for PulseStatistics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            high_pulses_sent: self.high_pulses_sent + other.high_pulses_sent,
+            low_pulses_sent: self.low_pulses_sent + other.low_pulses_sent,
+        }
+    }
+}
+
+impl Mul<u64> for PulseStatistics {
+    type Output = Self;
+
+    fn mul(self, times: u64) -> Self {
+        Self {
+            high_pulses_sent: self.high_pulses_sent * times,
+            low_pulses_sent: self.low_pulses_sent * times,
+        }
+    }
+}
+
+impl Sum for PulseStatistics {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        let mut high_pulses_sent = 0;
+        let mut low_pulses_sent = 0;
+        for item in iter {
+            high_pulses_sent += item.high_pulses_sent;
+            low_pulses_sent += item.low_pulses_sent
+        }
+        Self {
+            high_pulses_sent,
+            low_pulses_sent,
+        }
+    }
+}
+
+pub fn push_button(network: &mut Network) -> Result<PulseStatistics> {
+    network.press_button(|_| {})
+}
+
+enum ModuleKind {
+    FlipFlop(String),
+    Conjunction(String),
+    Broadcaster,
+    // Untyped deliberately omitted here,
+    // as it can't appear on the left side of the line
+}
+
+impl ModuleKind {
+    fn name(&self) -> &str {
+        match &self {
+            ModuleKind::FlipFlop(name) => name,
+            ModuleKind::Conjunction(name) => name,
+            ModuleKind::Broadcaster => "broadcaster",
+        }
+    }
+}
+
+impl FromStr for ModuleKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let kind = match s {
+            "broadcaster" => ModuleKind::Broadcaster,
+            _ => match s.chars().next() {
+                Some('&') => ModuleKind::Conjunction(String::from(&s[1..])),
+                Some('%') => ModuleKind::FlipFlop(String::from(&s[1..])),
+                _ => bail!("Don't know what module kind {s:?} represents"),
+            },
+        };
+        if kind.name().is_empty() {
+            bail!("Expected {s:?} to include a module name")
+        }
+        Ok(kind)
+    }
+}
+
+struct LineInfo {
+    kind: ModuleKind,
+    connections: Vec<String>,
+}
+
+impl FromStr for LineInfo {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [left, right] = Vec::from_iter(s.split("->").map(|side| side.trim()))[..] else {
+            bail!("Expected every line to have an arrow in the middle!")
+        };
+        let kind = ModuleKind::from_str(left)?;
+        let connections = if right.is_empty() {
+            vec![]
+        } else {
+            Vec::from_iter(right.split(',').map(|x| x.trim().to_string()))
+        };
+        Ok(Self { kind, connections })
+    }
+}
+
+impl FromStr for Network {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let lines = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                let lineno = i + 1;
+                let info = LineInfo::from_str(line)
+                    .with_context(|| format!("Failed to parse line {lineno}: {line:?}"))?;
+                Ok((lineno, info))
+            })
+            .collect::<Result<Vec<(usize, LineInfo)>>>()?;
+
+        // Intern every name first (defined modules, then untyped ones),
+        // so that connections can be resolved to indices
+        let mut names: Vec<String> = vec![];
+        let mut indices: HashMap<String, ModuleId> = HashMap::new();
+        for (lineno, line) in &lines {
+            let name = line.kind.name();
+            if indices.contains_key(name) {
+                bail!("Module {name:?} is defined a second time on line {lineno}")
+            }
+            indices.insert(name.to_string(), names.len().try_into()?);
+            names.push(name.to_string());
+        }
+        for name in lines.iter().flat_map(|(_, l)| l.connections.iter()) {
+            if !indices.contains_key(name) {
+                indices.insert(name.to_string(), names.len().try_into()?);
+                names.push(name.to_string());
+            }
+        }
+
+        let mut inputs: Vec<Vec<ModuleId>> = vec![vec![]; names.len()];
+        let mut modules = Vec::from_iter((0..names.len()).map(|_| Module::Untyped));
+
+        for (_, line) in &lines {
+            let id = indices[line.kind.name()];
+            let connections = Vec::from_iter(line.connections.iter().map(|c| indices[c]));
+            for &conn in &connections {
+                inputs[conn as usize].push(id)
+            }
+            modules[id as usize] = match &line.kind {
+                ModuleKind::Broadcaster => Module::Broadcast { connections },
+                ModuleKind::FlipFlop(_) => Module::FlipFlop {
+                    connections,
+                    is_on: false,
+                },
+                ModuleKind::Conjunction(_) => Module::Conjunction {
+                    connections,
+                    memory: vec![],
+                },
+            };
+        }
+
+        for (module, module_inputs) in modules.iter_mut().zip(inputs) {
+            if let Module::Conjunction { memory, .. } = module {
+                *memory = Vec::from_iter(module_inputs.into_iter().map(|i| (i, PulseKind::Low)))
+            }
+        }
+
+        Ok(Network {
+            modules,
+            names,
+            indices,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::{push_button, LineInfo, Module, ModuleKind, Network, PulseKind, PulseStatistics};
+
+    const FIRST_EXAMPLE: &str = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+
+    const SECOND_EXAMPLE: &str = "broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
+
+    #[test]
+    fn test_parsing_broadcaster_line() {
+        let line = LineInfo::from_str("broadcaster -> a, b, c").unwrap();
+        assert!(matches!(line.kind, ModuleKind::Broadcaster));
+        assert_eq!(line.connections, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_parsing_interns_every_name() {
+        let network = Network::from_str("broadcaster -> a, b, c\n%a -> output").unwrap();
+        let broadcaster = network.index_of("broadcaster").unwrap();
+        let connections = Vec::from_iter(
+            network.modules[broadcaster as usize]
+                .connections()
+                .iter()
+                .map(|&id| network.name_of(id)),
+        );
+        assert_eq!(connections, vec!["a", "b", "c"]);
+        assert!(network.index_of("output").is_some());
+        assert!(network.index_of("nonexistent").is_none());
+    }
+
+    #[test]
+    fn test_first_example_pulses_per_press() {
+        let mut network = Network::from_str(FIRST_EXAMPLE).unwrap();
+        for _ in 0..10 {
+            let statistics = push_button(&mut network).unwrap();
+            assert_eq!(statistics.low_pulses_sent, 8);
+            assert_eq!(statistics.high_pulses_sent, 4);
+        }
+    }
+
+    #[test]
+    fn test_summing_statistics_counts_each_button_pulse_once() {
+        let mut network = Network::from_str(FIRST_EXAMPLE).unwrap();
+        let total: PulseStatistics = (0..1000).map(|_| push_button(&mut network).unwrap()).sum();
+        assert_eq!(total.low_pulses_sent, 8000);
+        assert_eq!(total.high_pulses_sent, 4000);
+        assert_eq!(PulseStatistics::new().low_pulses_sent, 0);
+    }
+
+    #[test]
+    fn test_second_example_totals() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let total: PulseStatistics = (0..1000).map(|_| push_button(&mut network).unwrap()).sum();
+        assert_eq!(total.low_pulses_sent, 4250);
+        assert_eq!(total.high_pulses_sent, 2750);
+        assert_eq!(total.multiply(), 11687500);
+    }
+
+    #[test]
+    fn test_connection_to_undefined_module_is_untyped() {
+        let mut network = Network::from_str("broadcaster -> a, ghost\n%a -> ghost").unwrap();
+        let ghost = network.index_of("ghost").unwrap();
+        assert!(matches!(network.modules[ghost as usize], Module::Untyped));
+        let statistics = push_button(&mut network).unwrap();
+        // button -> broadcaster, broadcaster -> a, broadcaster -> ghost, a -> ghost
+        assert_eq!(statistics.low_pulses_sent, 3);
+        assert_eq!(statistics.high_pulses_sent, 1);
+    }
+
+    #[test]
+    fn test_missing_broadcaster_errors() {
+        let mut network = Network::from_str("%a -> b\n%b -> a").unwrap();
+        let error = push_button(&mut network).unwrap_err();
+        assert!(error.to_string().contains("broadcaster"), "{error}");
+    }
+
+    #[test]
+    fn test_reset_restores_initial_state() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let initial_state = network.state();
+        push_button(&mut network).unwrap();
+        assert_ne!(network.state(), initial_state);
+        network.reset();
+        assert_eq!(network.state(), initial_state);
+    }
+
+    #[test]
+    fn test_pressing_again_after_reset() {
+        for example in [FIRST_EXAMPLE, SECOND_EXAMPLE] {
+            let mut network = Network::from_str(example).unwrap();
+            let initial_state = network.state();
+            // Both examples are back in their initial state after 1000 presses,
+            // so also stop at 999, when the second one isn't
+            for presses in [1000, 999] {
+                let run = |network: &mut Network| -> PulseStatistics {
+                    (0..presses).map(|_| push_button(network).unwrap()).sum()
+                };
+                let first = run(&mut network);
+                network.reset();
+                assert_eq!(network.state(), initial_state);
+                let second = run(&mut network);
+                network.reset();
+                assert_eq!(first, second, "{presses} presses of\n{example}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parsing_published_examples() {
+        let network = Network::from_str(FIRST_EXAMPLE).unwrap();
+        assert_eq!(network.modules.len(), 5);
+        let network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        assert_eq!(network.modules.len(), 6);
+        let con = network.index_of("con").unwrap();
+        let Module::Conjunction { memory, .. } = &network.modules[con as usize] else {
+            panic!("Expected `con` to be a conjunction module")
+        };
+        let inputs = Vec::from_iter(memory.iter().map(|(id, _)| network.name_of(*id)));
+        assert_eq!(inputs, vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_parsing_rejects_duplicate_definitions() {
+        let error = Network::from_str("broadcaster -> a\n%a -> b\n&a -> b").unwrap_err();
+        let message = error.to_string();
+        assert!(message.contains("\"a\""), "{message}");
+        assert!(message.contains("line 3"), "{message}");
+    }
+
+    #[test]
+    fn test_parsing_errors_include_line_numbers() {
+        let error = Network::from_str("broadcaster -> a\n%a -> b\n$b -> a").unwrap_err();
+        let message = format!("{error:#}");
+        assert!(message.contains("line 3"), "{message}");
+        assert!(message.contains("$b"), "{message}");
+    }
+
+    #[test]
+    fn test_parsing_module_with_no_connections() {
+        let mut network = Network::from_str("broadcaster -> a\n%a ->").unwrap();
+        let a = network.index_of("a").unwrap();
+        assert!(network.modules[a as usize].connections().is_empty());
+        let statistics = push_button(&mut network).unwrap();
+        assert_eq!(statistics.low_pulses_sent, 2);
+        assert_eq!(statistics.high_pulses_sent, 0);
+    }
+
+    #[test]
+    fn test_broadcaster_with_no_connections() {
+        // The button's pulse is the only one sent
+        let mut network = Network::from_str("broadcaster ->").unwrap();
+        let statistics = push_button(&mut network).unwrap();
+        assert_eq!(statistics.low_pulses_sent, 1);
+        assert_eq!(statistics.high_pulses_sent, 0);
+        let mut network = Network::from_str("broadcaster ->\n%a -> b\n&b -> a").unwrap();
+        let total: PulseStatistics = (0..1000).map(|_| push_button(&mut network).unwrap()).sum();
+        assert_eq!(total.low_pulses_sent, 1000);
+        assert_eq!(total.multiply(), 0);
+    }
+
+    fn trace_press(network: &mut Network) -> Vec<String> {
+        let mut events = vec![];
+        network.press_button(|event| events.push(*event)).unwrap();
+        Vec::from_iter(events.iter().map(|e| network.describe_event(e)))
+    }
+
+    #[test]
+    fn test_first_example_trace() {
+        let mut network = Network::from_str(FIRST_EXAMPLE).unwrap();
+        let expected = "\
+button -low-> broadcaster
+broadcaster -low-> a
+broadcaster -low-> b
+broadcaster -low-> c
+a -high-> b
+b -high-> c
+c -high-> inv
+inv -low-> a
+a -low-> b
+b -low-> c
+c -low-> inv
+inv -high-> a";
+        assert_eq!(trace_press(&mut network).join("\n"), expected);
+    }
+
+    #[test]
+    fn test_second_example_trace() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let expected_presses = [
+            "\
+button -low-> broadcaster
+broadcaster -low-> a
+a -high-> inv
+a -high-> con
+inv -low-> b
+con -high-> output
+b -high-> con
+con -low-> output",
+            "\
+button -low-> broadcaster
+broadcaster -low-> a
+a -low-> inv
+a -low-> con
+inv -high-> b
+con -high-> output",
+            "\
+button -low-> broadcaster
+broadcaster -low-> a
+a -high-> inv
+a -high-> con
+inv -low-> b
+con -low-> output
+b -low-> con
+con -high-> output",
+            "\
+button -low-> broadcaster
+broadcaster -low-> a
+a -low-> inv
+a -low-> con
+inv -high-> b
+con -high-> output",
+        ];
+        for (i, expected) in expected_presses.iter().enumerate() {
+            let trace = trace_press(&mut network).join("\n");
+            assert_eq!(&trace, expected, "Trace differs on press {}", i + 1);
+        }
+    }
+
+    #[test]
+    fn test_press_until() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let output = network.index_of("output").unwrap();
+        let press = network
+            .press_until(10, |e| e.receiver == output && e.kind == PulseKind::Low)
+            .unwrap();
+        assert_eq!(press, Some(1));
+
+        // `b` is only ever sent low pulses on odd-numbered presses
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let b = network.index_of("b").unwrap();
+        let mut presses = vec![];
+        for _ in 0..3 {
+            presses.push(
+                network
+                    .press_until(10, |e| e.receiver == b && e.kind == PulseKind::Low)
+                    .unwrap(),
+            );
+        }
+        assert_eq!(presses, vec![Some(1), Some(2), Some(2)]);
+
+        // `con` never receives a pulse from `output`
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let press = network
+            .press_until(10, |e| e.sender == Some(output))
+            .unwrap();
+        assert_eq!(press, None);
+    }
+
+    #[test]
+    fn test_pulses_are_delivered_in_fifo_order() {
+        // `con` gets interleaved pulses from `a` and `inv`, and its own output
+        // feeds back into `a`, so any deviation from a strict FIFO shows up here
+        let mut network = Network::from_str(
+            "broadcaster -> a, inv
+%a -> con
+&inv -> con
+&con -> a, out",
+        )
+        .unwrap();
+        let expected = "\
+button -low-> broadcaster
+broadcaster -low-> a
+broadcaster -low-> inv
+a -high-> con
+inv -high-> con
+con -high-> a
+con -high-> out
+con -low-> a
+con -low-> out
+a -low-> con
+con -high-> a
+con -high-> out";
+        assert_eq!(trace_press(&mut network).join("\n"), expected);
+    }
+
+    #[test]
+    fn test_dump() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let expected_after_first_press = "\
+broadcaster (broadcast)
+%a: on
+&inv: a=high
+%b: on
+&con: a=high, b=high
+output (untyped)";
+        push_button(&mut network).unwrap();
+        assert_eq!(network.dump(), expected_after_first_press);
+
+        // `a` turns off again, while `b` only receives a high pulse and so stays on
+        let expected_after_second_press = "\
+broadcaster (broadcast)
+%a: off
+&inv: a=low
+%b: on
+&con: a=low, b=high
+output (untyped)";
+        push_button(&mut network).unwrap();
+        assert_eq!(network.dump(), expected_after_second_press);
+    }
+}
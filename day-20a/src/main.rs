@@ -1,342 +1,165 @@
-use std::collections::{HashMap, VecDeque};
 use std::fs::read_to_string;
-use std::iter::Sum;
 use std::str::FromStr;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
+use day_20a::{push_button, Network, PulseStatistics};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum PulseKind {
-    High,
-    Low,
-}
-
-struct PulseRequest {
-    kind: PulseKind,
-    sender: String,
-}
+const BUTTON_PRESSES: u64 = 1000;
 
-trait Module {
-    fn name(&self) -> &str;
-    fn connections(&self) -> &Vec<String>;
-    fn receive_pulse(&mut self, kind: &PulseKind, from_: &str) -> Option<PulseRequest>;
-    fn send_pulse(&self, kind: &PulseKind) -> Option<PulseRequest> {
-        Some(PulseRequest {
-            kind: *kind,
-            sender: self.name().to_string(),
-        })
-    }
+#[derive(Default)]
+struct Options {
+    /// Print every pulse delivered during the first `trace` presses
+    trace: u64,
+    /// Print the state of every module after each of the first `dump` presses
+    dump: u64,
 }
 
-struct FlipFlopModule {
-    _name: String,
-    _connections: Vec<String>,
-    is_on: bool,
+fn parse_count(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<u64> {
+    let Some(n) = args.next() else {
+        bail!("Expected a number of presses after `{flag}`")
+    };
+    n.parse()
+        .with_context(|| format!("Invalid number of presses for `{flag}`: {n}"))
 }
 
-impl FlipFlopModule {
-    fn new(name: &str, connections: &[String]) -> Self {
-        Self {
-            _name: name.to_string(),
-            _connections: Vec::from(connections),
-            is_on: false,
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => options.trace = parse_count(&arg, &mut args)?,
+            "--dump" => options.dump = parse_count(&arg, &mut args)?,
+            _ => bail!("Unrecognised argument {arg:?}"),
         }
     }
+    Ok(options)
 }
 
-impl Module for FlipFlopModule {
-    fn name(&self) -> &str {
-        self._name.as_str()
-    }
-
-    fn connections(&self) -> &Vec<String> {
-        &self._connections
-    }
-
-    fn receive_pulse(&mut self, kind: &PulseKind, _: &str) -> Option<PulseRequest> {
-        match (self.is_on, kind) {
-            (_, PulseKind::High) => None,
-            (true, PulseKind::Low) => {
-                self.is_on = false;
-                self.send_pulse(&PulseKind::Low)
-            }
-            (false, PulseKind::Low) => {
-                self.is_on = true;
-                self.send_pulse(&PulseKind::High)
-            }
-        }
+fn press_and_trace(network: &mut Network, press: u64) -> Result<PulseStatistics> {
+    let mut events = vec![];
+    let statistics = network.press_button(|event| events.push(*event))?;
+    eprintln!("Press {press}:");
+    for event in events {
+        eprintln!("{}", network.describe_event(&event))
     }
+    Ok(statistics)
 }
 
-struct ConjunctionModule {
-    _name: String,
-    _connections: Vec<String>,
-    memory: HashMap<String, PulseKind>,
-}
-
-impl ConjunctionModule {
-    fn new(name: &str, connections: &[String], inputs: &[String]) -> Self {
-        Self {
-            _name: name.to_string(),
-            _connections: Vec::from(connections),
-            memory: HashMap::from_iter(inputs.iter().map(|s| (s.to_owned(), PulseKind::Low))),
-        }
-    }
-}
-
-impl Module for ConjunctionModule {
-    fn name(&self) -> &str {
-        self._name.as_str()
-    }
-
-    fn connections(&self) -> &Vec<String> {
-        &self._connections
-    }
-
-    fn receive_pulse(&mut self, kind: &PulseKind, from_: &str) -> Option<PulseRequest> {
-        debug_assert!(self.memory.contains_key(from_));
-        self.memory.insert(from_.to_string(), *kind);
-        if self.memory.values().all(|k| k == &PulseKind::High) {
-            self.send_pulse(&PulseKind::Low)
+/// Press the button `presses` times. If the network returns to its initial state
+/// before then, the remaining presses just repeat that cycle, so the totals
+/// can be extrapolated rather than simulated (and aren't traced or dumped).
+fn count_pulses(network: &mut Network, presses: u64, options: &Options) -> Result<PulseStatistics> {
+    let initial_state = network.state();
+    let mut history = vec![];
+    for press in 1..=presses {
+        let statistics = if press <= options.trace {
+            press_and_trace(network, press)
         } else {
-            self.send_pulse(&PulseKind::High)
+            push_button(network)
         }
-    }
-}
-
-struct BroadcastModule {
-    _connections: Vec<String>,
-}
-
-impl BroadcastModule {
-    fn new(connections: &[String]) -> Self {
-        Self {
-            _connections: Vec::from(connections),
+        .with_context(|| format!("Failed on button press {press}"))?;
+        if press <= options.dump {
+            eprintln!("After press {press}:\n{}", network.dump());
+        }
+        history.push(statistics);
+        if network.state() == initial_state {
+            let cycle: PulseStatistics = history.iter().copied().sum();
+            let remainder: PulseStatistics = history[..((presses % press) as usize)]
+                .iter()
+                .copied()
+                .sum();
+            return Ok(cycle * (presses / press) + remainder);
         }
     }
+    Ok(history.into_iter().sum())
 }
 
-impl Module for BroadcastModule {
-    fn name(&self) -> &str {
-        "broadcaster"
-    }
-
-    fn connections(&self) -> &Vec<String> {
-        &self._connections
-    }
-
-    fn receive_pulse(&mut self, kind: &PulseKind, _: &str) -> Option<PulseRequest> {
-        self.send_pulse(kind)
-    }
-}
-
-struct UntypedModule {
-    _name: String,
-    _connections: Vec<String>,
+fn solve(mut network: Network, options: &Options) -> Result<u64> {
+    Ok(count_pulses(&mut network, BUTTON_PRESSES, options)?.multiply())
 }
 
-impl UntypedModule {
-    fn new(name: &str) -> Self {
-        Self {
-            _name: name.to_string(),
-            _connections: vec![],
-        }
-    }
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    let network = Network::from_str(&input).unwrap();
+    println!("{}", solve(network, &options).unwrap())
 }
 
-impl Module for UntypedModule {
-    fn connections(&self) -> &Vec<String> {
-        &self._connections
-    }
-
-    fn name(&self) -> &str {
-        self._name.as_str()
-    }
-
-    fn receive_pulse(&mut self, _: &PulseKind, _: &str) -> Option<PulseRequest> {
-        None
-    }
-}
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
 
-struct PulseStatistics {
-    high_pulses_sent: u32,
-    low_pulses_sent: u32,
-}
+    use day_20a::{push_button, Network, PulseStatistics};
 
-impl PulseStatistics {
-    fn new() -> Self {
-        Self {
-            high_pulses_sent: 0,
-            low_pulses_sent: 1,
-        }
-    }
+    use crate::{count_pulses, parse_args, solve, Options};
 
-    fn update(&mut self, kind: &PulseKind) {
-        match kind {
-            PulseKind::High => self.high_pulses_sent += 1,
-            PulseKind::Low => self.low_pulses_sent += 1,
-        }
-    }
+    const SECOND_EXAMPLE: &str = "broadcaster -> a
+%a -> inv, con
+&inv -> b
+%b -> con
+&con -> output";
 
-    fn multiply(&self) -> u32 {
-        self.high_pulses_sent * self.low_pulses_sent
+    #[test]
+    fn test_first_example() {
+        let example = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+        let network = Network::from_str(example).unwrap();
+        assert_eq!(solve(network, &Options::default()).unwrap(), 32000000)
     }
-}
 
-impl Sum for PulseStatistics {
-    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
-        let mut high_pulses_sent = 0;
-        let mut low_pulses_sent = 0;
-        for item in iter {
-            high_pulses_sent += item.high_pulses_sent;
-            low_pulses_sent += item.low_pulses_sent
-        }
-        Self {
-            high_pulses_sent,
-            low_pulses_sent,
-        }
+    #[test]
+    fn test_second_example() {
+        let network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        assert_eq!(solve(network, &Options::default()).unwrap(), 11687500)
     }
-}
 
-fn push_button(puzzle_input: &mut HashMap<String, Box<dyn Module>>) -> PulseStatistics {
-    let first_request = puzzle_input
-        .get_mut("broadcaster")
-        .expect("Expected there to be a broadcaster in this map!")
-        .receive_pulse(&PulseKind::Low, "button");
-    let Some(first_request) = first_request else {
-        panic!("Wasn't expecting this to be None!")
-    };
-    let mut pulse_requests = VecDeque::from([first_request]);
-    let mut statistics = PulseStatistics::new();
-    loop {
-        let Some(request) = pulse_requests.pop_front() else {
-            break;
-        };
-        let connections = Vec::from_iter(
-            puzzle_input[&request.sender]
-                .connections()
-                .iter()
-                .map(|s| s.to_owned()),
-        );
-        for conn_name in connections {
-            statistics.update(&request.kind);
-            if let Some(new_request) = puzzle_input
-                .get_mut(&conn_name)
-                .unwrap()
-                .receive_pulse(&request.kind, &request.sender)
-            {
-                pulse_requests.push_back(new_request)
-            }
-        }
+    #[test]
+    fn test_missing_broadcaster_error_names_module_and_press() {
+        let network = Network::from_str("%a -> b\n%b -> a").unwrap();
+        let error = format!("{:#}", solve(network, &Options::default()).unwrap_err());
+        assert!(error.contains("press 1"), "{error}");
+        assert!(error.contains("broadcaster"), "{error}");
     }
-    debug_assert!(statistics.high_pulses_sent > 0 || statistics.low_pulses_sent > 1);
-    statistics
-}
 
-fn solve(mut node_map: HashMap<String, Box<dyn Module>>) -> u32 {
-    (0..1000)
-        .map(|_| push_button(&mut node_map))
-        .sum::<PulseStatistics>()
-        .multiply()
-}
-
-enum ModuleKind {
-    FlipFlop(String),
-    Conjunction(String),
-    Broadcaster,
-    // Untyped deliberately omitted here,
-    // as it can't appear on the left side of the line
-}
-
-impl ModuleKind {
-    fn name(&self) -> String {
-        match &self {
-            ModuleKind::FlipFlop(name) => name.to_owned(),
-            ModuleKind::Conjunction(name) => name.to_owned(),
-            ModuleKind::Broadcaster => String::from("broadcaster"),
+    #[test]
+    fn test_second_example_cycles_with_period_four() {
+        let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+        let initial_state = network.state();
+        for _ in 0..3 {
+            push_button(&mut network).unwrap();
+            assert_ne!(network.state(), initial_state);
         }
-    }
-}
-
-impl FromStr for ModuleKind {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        match s {
-            "broadcaster" => Ok(ModuleKind::Broadcaster),
-            _ => match s.chars().next().unwrap() {
-                '&' => Ok(ModuleKind::Conjunction(String::from(&s[1..]))),
-                '%' => Ok(ModuleKind::FlipFlop(String::from(&s[1..]))),
-                _ => bail!("Don't know what module kind {s} represents"),
-            },
+        push_button(&mut network).unwrap();
+        assert_eq!(network.state(), initial_state);
+    }
+
+    #[test]
+    fn test_extrapolation_matches_brute_force() {
+        for presses in [1, 3, 4, 5, 999, 1000] {
+            let mut network = Network::from_str(SECOND_EXAMPLE).unwrap();
+            let extrapolated = count_pulses(&mut network, presses, &Options::default()).unwrap();
+            network.reset();
+            let brute_force: PulseStatistics = (0..presses)
+                .map(|_| push_button(&mut network).unwrap())
+                .sum();
+            assert_eq!(
+                extrapolated, brute_force,
+                "Mismatch after {presses} presses"
+            );
         }
     }
-}
-
-struct LineInfo {
-    kind: ModuleKind,
-    connections: Vec<String>,
-}
-
-impl FromStr for LineInfo {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let [left, right] = Vec::from_iter(s.trim().split(" -> "))[..] else {
-            bail!("Expected every line to have an arrow in the middle!")
-        };
-        let kind = ModuleKind::from_str(left)?;
-        let connections = Vec::from_iter(right.split(", ").map(|x| x.to_string()));
-        Ok(Self { kind, connections })
-    }
-}
-
-fn parse_input(input_lines: Vec<&str>) -> Result<HashMap<String, Box<dyn Module>>> {
-    let lines = input_lines
-        .iter()
-        .map(|l| l.parse())
-        .collect::<Result<Vec<LineInfo>>>()?;
-
-    let mut modules = HashMap::new();
-
-    for line in &lines {
-        let (name, module): (String, Box<dyn Module>) = match &line.kind {
-            ModuleKind::Broadcaster => (
-                String::from("broadcaster"),
-                Box::new(BroadcastModule::new(&line.connections)),
-            ),
-            ModuleKind::FlipFlop(name) => (
-                name.to_string(),
-                Box::new(FlipFlopModule::new(name, &line.connections)),
-            ),
-            ModuleKind::Conjunction(name) => {
-                let inputs = &lines
-                    .iter()
-                    .filter(|l| l.connections.contains(name))
-                    .map(|l| l.kind.name())
-                    .collect::<Vec<String>>();
-                (
-                    name.to_owned(),
-                    Box::new(ConjunctionModule::new(name, &line.connections, inputs)),
-                )
-            }
-        };
-        modules.insert(name.to_owned(), module);
-    }
 
-    for line in &lines {
-        for name in &line.connections {
-            modules
-                .entry(name.to_owned())
-                .or_insert(Box::new(UntypedModule::new(name)));
-        }
+    #[test]
+    fn test_parsing_trace_flag() {
+        let args = ["--trace", "3"].map(String::from);
+        assert_eq!(parse_args(args.into_iter()).unwrap().trace, 3);
+        let args = ["--dump", "2", "--trace", "1"].map(String::from);
+        let options = parse_args(args.into_iter()).unwrap();
+        assert_eq!((options.dump, options.trace), (2, 1));
+        assert_eq!(parse_args(std::iter::empty()).unwrap().trace, 0);
+        assert!(parse_args(["--trace"].map(String::from).into_iter()).is_err());
+        assert!(parse_args(["--trace", "x"].map(String::from).into_iter()).is_err());
     }
-
-    Ok(modules)
-}
-
-fn main() {
-    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
-    let modules = parse_input(Vec::from_iter(input.lines())).unwrap();
-    println!("{}", solve(modules))
 }
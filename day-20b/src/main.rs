@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use day_20a::{ModuleId, Network, PulseKind};
+
+const MAX_PRESSES: u64 = 1_000_000;
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: u64, b: u64) -> u64 {
+    a / gcd(a, b) * b
+}
+
+/// Find the single conjunction module that sends pulses to `rx`,
+/// and the IDs of all the modules that send pulses to *that* module.
+fn find_feeder_inputs(network: &Network) -> Result<(ModuleId, Vec<ModuleId>)> {
+    let Some(rx) = network.index_of("rx") else {
+        bail!("Expected there to be an `rx` module in the network!")
+    };
+    let feeders = Vec::from_iter(
+        network
+            .modules()
+            .filter(|(_, m)| m.connections().contains(&rx)),
+    );
+    let (feeder, feeder_module) = match feeders[..] {
+        [] => bail!("Expected there to be a module sending pulses to `rx`!"),
+        [feeder] => feeder,
+        _ => bail!("Expected exactly one module to send pulses to `rx`!"),
+    };
+    if !feeder_module.is_conjunction() {
+        bail!(
+            "Expected the module feeding `rx` to be a conjunction module, but {} isn't",
+            network.name_of(feeder)
+        )
+    }
+    let inputs = Vec::from_iter(
+        network
+            .modules()
+            .filter(|(_, m)| m.connections().contains(&feeder))
+            .map(|(id, _)| id),
+    );
+    Ok((feeder, inputs))
+}
+
+/// `rx` only receives a low pulse once every input to its feeder conjunction
+/// has sent a high pulse during the same press. Each input does so periodically,
+/// so the answer is the lowest common multiple of the periods.
+fn solve(mut network: Network) -> Result<u64> {
+    let (feeder, feeder_inputs) = find_feeder_inputs(&network)?;
+    let mut first_high_pulses: HashMap<ModuleId, u64> = HashMap::new();
+    let mut periods: HashMap<ModuleId, u64> = HashMap::new();
+    let mut irregular = None;
+    // Every press starts with the button's pulse, so we can count presses as we go
+    let mut press = 0;
+    let finished = network.press_until(MAX_PRESSES, |event| {
+        match (event.sender, event.kind) {
+            (None, _) => press += 1,
+            (Some(sender), PulseKind::High) if event.receiver == feeder => {
+                match first_high_pulses.get(&sender) {
+                    None => {
+                        first_high_pulses.insert(sender, press);
+                    }
+                    Some(&first) if first == press || periods.contains_key(&sender) => {}
+                    Some(&first) if press == first * 2 => {
+                        periods.insert(sender, first);
+                    }
+                    Some(&first) => irregular = Some((sender, first, press)),
+                }
+            }
+            _ => {}
+        }
+        irregular.is_some() || periods.len() == feeder_inputs.len()
+    })?;
+    if let Some((sender, first, second)) = irregular {
+        bail!(
+            "Expected {} to send high pulses periodically, \
+            but it sent them on presses {first} and {second}",
+            network.name_of(sender)
+        )
+    }
+    if finished.is_none() {
+        bail!(
+            "Failed to find a period for every input to the `rx` feeder after {MAX_PRESSES} presses"
+        )
+    }
+    Ok(periods.values().copied().fold(1, lcm))
+}
+
+fn main() {
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    let network = Network::from_str(&input).unwrap();
+    println!("{}", solve(network).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use day_20a::Network;
+
+    use crate::{lcm, solve};
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(7, 13), 91);
+    }
+
+    #[test]
+    fn test_counter_network() {
+        let example = "broadcaster -> a, b1
+%a -> x
+&x -> fd
+%b1 -> b2
+%b2 -> y
+&y -> fd
+&fd -> rx";
+        let network = Network::from_str(example).unwrap();
+        assert_eq!(solve(network).unwrap(), 4)
+    }
+
+    #[test]
+    fn test_network_without_rx_errors() {
+        let example = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a";
+        let network = Network::from_str(example).unwrap();
+        let error = solve(network).unwrap_err();
+        assert!(error.to_string().contains("rx"), "{error}")
+    }
+
+    #[test]
+    fn test_rx_fed_by_flip_flop_errors() {
+        let example = "broadcaster -> a
+%a -> rx";
+        let network = Network::from_str(example).unwrap();
+        let error = solve(network).unwrap_err();
+        assert!(error.to_string().contains("conjunction"), "{error}")
+    }
+}
@@ -0,0 +1,321 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIs, EnumIter};
+
+#[derive(EnumIter)]
+pub enum Direction {
+    North,
+    South,
+    East,
+    West,
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct Point {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Point {
+    pub fn go(&self, direction: &Direction) -> Point {
+        let Point { x, y } = *self;
+        match direction {
+            Direction::North => Point { x, y: y - 1 },
+            Direction::South => Point { x, y: y + 1 },
+            Direction::East => Point { x: x + 1, y },
+            Direction::West => Point { x: x - 1, y },
+        }
+    }
+}
+
+/// A point on the infinitely tiled map
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct TiledPoint {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl TiledPoint {
+    pub fn go(&self, direction: &Direction) -> TiledPoint {
+        let TiledPoint { x, y } = *self;
+        match direction {
+            Direction::North => TiledPoint { x, y: y - 1 },
+            Direction::South => TiledPoint { x, y: y + 1 },
+            Direction::East => TiledPoint { x: x + 1, y },
+            Direction::West => TiledPoint { x: x - 1, y },
+        }
+    }
+}
+
+impl From<Point> for TiledPoint {
+    fn from(point: Point) -> Self {
+        TiledPoint {
+            x: point.x as i64,
+            y: point.y as i64,
+        }
+    }
+}
+
+#[derive(EnumIs)]
+pub enum Tile {
+    Start,
+    GardenPlot,
+    Rock,
+}
+
+impl TryFrom<&char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &char) -> Result<Self> {
+        match s {
+            'S' => Ok(Self::Start),
+            '.' => Ok(Self::GardenPlot),
+            '#' => Ok(Self::Rock),
+            _ => bail!("Don't know what kind of tile {s} is"),
+        }
+    }
+}
+
+pub struct PuzzleInput {
+    pub start: Point,
+    /// Whether each tile is a rock, row by row
+    rocks: Vec<bool>,
+    width: i16,
+    height: i16,
+}
+
+impl PuzzleInput {
+    pub fn width(&self) -> i16 {
+        self.width
+    }
+
+    pub fn height(&self) -> i16 {
+        self.height
+    }
+
+    /// Whether the tile at (`x`, `y`) is a rock. Panics if it's outside the grid.
+    pub fn is_rock(&self, x: i16, y: i16) -> bool {
+        assert!((0..self.width).contains(&x) && (0..self.height).contains(&y));
+        self.rocks[self.index_of(Point { x, y })]
+    }
+
+    /// The garden plots adjacent to `point`
+    pub fn points_from_here(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::iter().map(move |d| point.go(&d)).filter(|p| {
+            (0..self.width).contains(&p.x)
+                && (0..self.height).contains(&p.y)
+                && !self.is_rock(p.x, p.y)
+        })
+    }
+
+    /// Whether the tile at `point` on the infinitely tiled map is a rock
+    pub fn is_rock_wrapped(&self, point: TiledPoint) -> bool {
+        self.is_rock(
+            point.x.rem_euclid(self.width as i64) as i16,
+            point.y.rem_euclid(self.height as i64) as i16,
+        )
+    }
+
+    /// The garden plots adjacent to `point` on the infinitely tiled map
+    pub fn tiled_points_from_here(
+        &self,
+        point: TiledPoint,
+    ) -> impl Iterator<Item = TiledPoint> + '_ {
+        Direction::iter()
+            .map(move |d| point.go(&d))
+            .filter(|p| !self.is_rock_wrapped(*p))
+    }
+
+    fn index_of(&self, point: Point) -> usize {
+        (point.y as usize) * (self.width as usize) + (point.x as usize)
+    }
+
+    /// The length of the shortest path from the start to every reachable garden plot
+    pub fn bfs_distances(&self) -> DistanceMap {
+        let mut distances = vec![None; self.rocks.len()];
+        distances[self.index_of(self.start)] = Some(0);
+        let mut queue = VecDeque::from([self.start]);
+        while let Some(point) = queue.pop_front() {
+            let distance = distances[self.index_of(point)].map(|d| d + 1);
+            for neighbour in self.points_from_here(point) {
+                let entry = &mut distances[self.index_of(neighbour)];
+                if entry.is_none() {
+                    *entry = distance;
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        DistanceMap {
+            distances,
+            width: self.width,
+        }
+    }
+
+    /// Render the grid, showing each reachable plot's distance from the start (mod 10)
+    pub fn render_distances(&self, distance_map: &DistanceMap) -> String {
+        let mut rendered = String::new();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                rendered.push(match distance_map.get(Point { x, y }) {
+                    Some(distance) => char::from_digit(distance % 10, 10).unwrap(),
+                    None if self.is_rock(x, y) => '#',
+                    None => '.',
+                })
+            }
+            rendered.push('\n')
+        }
+        rendered
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn of(n: u64) -> Parity {
+        if n.is_multiple_of(2) {
+            Parity::Even
+        } else {
+            Parity::Odd
+        }
+    }
+}
+
+/// Breadth-first search outwards from the start across the infinitely tiled map,
+/// one step at a time.
+///
+/// The grid is bipartite, so every plot reachable in exactly `n` steps can also be
+/// reached in `n + 2`, `n + 4`... steps; the number of plots reachable after
+/// exactly `n` steps is therefore the number of plots whose distance from the
+/// start is at most `n` and has the same parity as `n`.
+pub struct TiledWalk<'a> {
+    puzzle_input: &'a PuzzleInput,
+    previous_frontier: HashSet<TiledPoint>,
+    frontier: HashSet<TiledPoint>,
+    /// Number of plots found so far at an even/odd distance from the start
+    totals_by_parity: [u64; 2],
+    /// `reachable[n]` is the number of plots reachable in exactly `n` steps
+    reachable: Vec<u64>,
+}
+
+impl<'a> TiledWalk<'a> {
+    pub fn new(puzzle_input: &'a PuzzleInput) -> Self {
+        TiledWalk {
+            puzzle_input,
+            previous_frontier: HashSet::new(),
+            frontier: HashSet::from([puzzle_input.start.into()]),
+            totals_by_parity: [1, 0],
+            reachable: vec![1],
+        }
+    }
+
+    fn step(&mut self) {
+        let mut next_frontier = HashSet::new();
+        for &point in &self.frontier {
+            for neighbour in self.puzzle_input.tiled_points_from_here(point) {
+                if !self.previous_frontier.contains(&neighbour)
+                    && !self.frontier.contains(&neighbour)
+                {
+                    next_frontier.insert(neighbour);
+                }
+            }
+        }
+        self.previous_frontier = std::mem::replace(&mut self.frontier, next_frontier);
+        let parity = self.reachable.len() % 2;
+        self.totals_by_parity[parity] += self.frontier.len() as u64;
+        self.reachable.push(self.totals_by_parity[parity]);
+    }
+
+    /// The number of plots reachable in exactly `steps` steps
+    pub fn reachable_after(&mut self, steps: u64) -> u64 {
+        while self.reachable.len() as u64 <= steps {
+            self.step()
+        }
+        self.reachable[steps as usize]
+    }
+}
+
+/// The length of the shortest path from the start to each tile on the grid
+/// (`None` for rocks and unreachable plots)
+pub struct DistanceMap {
+    distances: Vec<Option<u32>>,
+    width: i16,
+}
+
+impl DistanceMap {
+    pub fn get(&self, point: Point) -> Option<u32> {
+        self.distances[(point.y as usize) * (self.width as usize) + (point.x as usize)]
+    }
+
+    /// The number of plots at most `steps` away from the start
+    /// whose distance from the start has the given parity
+    pub fn count_within(&self, steps: u64, parity: Parity) -> usize {
+        self.distances
+            .iter()
+            .flatten()
+            .map(|&d| d as u64)
+            .filter(|&d| d <= steps && Parity::of(d) == parity)
+            .count()
+    }
+
+    /// The distance to the furthest reachable plot
+    pub fn max_distance(&self) -> u32 {
+        self.distances.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+impl FromStr for PuzzleInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut rocks = vec![];
+        let (mut width, mut height) = (0, 0);
+        let mut start = None;
+        for (y, line) in s.lines().enumerate() {
+            let y = y.try_into()?;
+            height = y + 1;
+            let line_width = line.chars().count().try_into()?;
+            if y == 0 {
+                width = line_width
+            } else if line_width != width {
+                bail!("Line {y} has {line_width} tiles, but line 0 has {width}")
+            }
+            for (x, c) in line.chars().enumerate() {
+                let tile = Tile::try_from(&c)?;
+                if tile.is_start() {
+                    let point = Point {
+                        x: x.try_into()?,
+                        y,
+                    };
+                    if let Some(previous) = start {
+                        bail!("Found a second starting position at {point:?}; the first was at {previous:?}")
+                    }
+                    start = Some(point);
+                };
+                // The start is recorded separately; otherwise, it's just a garden plot
+                rocks.push(tile.is_rock());
+            }
+        }
+        let Some(start) = start else {
+            bail!("Couldn't find the starting position!")
+        };
+        Ok(PuzzleInput {
+            start,
+            rocks,
+            width,
+            height,
+        })
+    }
+}
+
+pub fn parse_input(filename: &str) -> Result<PuzzleInput> {
+    let input = read_to_string(filename)?;
+    PuzzleInput::from_str(&input)
+}
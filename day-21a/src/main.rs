@@ -1,130 +1,184 @@
-use std::collections::{HashMap, HashSet};
-use std::fs::read_to_string;
-use std::str::FromStr;
-
-use anyhow::{bail, Result};
-use strum::IntoEnumIterator;
-use strum_macros::{EnumIs, EnumIter};
-
-#[derive(EnumIter)]
-enum Direction {
-    North,
-    South,
-    East,
-    West,
-}
+use anyhow::{bail, Context, Result};
+use day_21a::{parse_input, Parity, PuzzleInput};
+
+const DEFAULT_STEPS: u64 = 64;
 
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct Point {
-    x: i16,
-    y: i16,
+struct Options {
+    /// How many steps the elf takes
+    steps: u64,
+    /// Print the grid with each plot's distance from the start
+    show_distances: bool,
 }
 
-impl Point {
-    fn go(&self, direction: &Direction) -> Point {
-        let Point { x, y } = *self;
-        match direction {
-            Direction::North => Point { x, y: y - 1 },
-            Direction::South => Point { x, y: y + 1 },
-            Direction::East => Point { x: x + 1, y },
-            Direction::West => Point { x: x - 1, y },
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            steps: DEFAULT_STEPS,
+            show_distances: false,
         }
     }
 }
 
-#[derive(EnumIs)]
-enum Tile {
-    Start,
-    GardenPlot,
-    Rock,
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--steps" => {
+                let Some(n) = args.next() else {
+                    bail!("Expected a number of steps after `--steps`")
+                };
+                options.steps = n
+                    .parse()
+                    .with_context(|| format!("Invalid number of steps: {n}"))?
+            }
+            "--show-distances" => options.show_distances = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
 }
 
-impl TryFrom<&char> for Tile {
-    type Error = anyhow::Error;
-
-    fn try_from(s: &char) -> Result<Self> {
-        match s {
-            'S' => Ok(Self::Start),
-            '.' => Ok(Self::GardenPlot),
-            '#' => Ok(Self::Rock),
-            _ => bail!("Don't know what kind of tile {s} is"),
-        }
+/// Every plot reachable in `n` steps is also reachable in `n + 2` steps
+/// (by stepping back and forth), and the grid is bipartite, so the plots
+/// reachable in exactly `steps` steps are those at most `steps` away
+/// whose distance has the same parity as `steps`
+fn solve(puzzle_input: &PuzzleInput, options: &Options) -> usize {
+    let distance_map = puzzle_input.bfs_distances();
+    if options.show_distances {
+        eprint!("{}", puzzle_input.render_distances(&distance_map));
     }
+    distance_map.count_within(options.steps, Parity::of(options.steps))
 }
 
-struct PuzzleInput {
-    start: Point,
-    map: HashMap<Point, Tile>,
-    max_x: i16,
-    max_y: i16,
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input = parse_input("input.txt").unwrap();
+    println!("{}", solve(&input, &options))
 }
 
-impl FromStr for PuzzleInput {
-    type Err = anyhow::Error;
-
-    fn from_str(s: &str) -> Result<Self> {
-        let mut map = HashMap::new();
-        let (mut max_x, mut max_y) = (0, 0);
-        let mut start = None;
-        for (y, line) in s.lines().enumerate() {
-            let y = y.try_into()?;
-            max_y = y;
-            for (x, c) in line.chars().enumerate() {
-                let x = x.try_into()?;
-                max_x = x;
-                let point = Point { x, y };
-                let tile = Tile::try_from(&c)?;
-                if tile.is_start() {
-                    start = Some(point);
-                };
-                map.insert(point, tile);
-            }
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use day_21a::{Parity, Point, PuzzleInput};
+
+    use crate::{parse_args, solve, Options, DEFAULT_STEPS};
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    /// The original approach: track the full set of reachable plots, step by step
+    fn count_reachable_by_stepping(puzzle_input: &PuzzleInput, steps: u64) -> usize {
+        let mut points = HashSet::from([puzzle_input.start]);
+        for _ in 0..steps {
+            points = HashSet::from_iter(
+                points
+                    .iter()
+                    .flat_map(|p| puzzle_input.points_from_here(*p)),
+            )
+        }
+        points.len()
+    }
+
+    #[test]
+    fn test_distances_match_stepping() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let distance_map = puzzle_input.bfs_distances();
+        for steps in 1..=10 {
+            assert_eq!(
+                distance_map.count_within(steps, Parity::of(steps)),
+                count_reachable_by_stepping(&puzzle_input, steps),
+                "{steps} steps"
+            );
         }
-        let Some(start) = start else {
-            bail!("Couldn't find the starting position!")
+    }
+
+    #[test]
+    fn test_example() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let options = Options {
+            steps: 6,
+            ..Options::default()
         };
-        Ok(PuzzleInput {
-            start,
-            map,
-            max_x,
-            max_y,
-        })
+        assert_eq!(solve(&puzzle_input, &options), 16);
     }
-}
 
-fn parse_input(filename: &str) -> Result<PuzzleInput> {
-    let input = read_to_string(filename)?;
-    PuzzleInput::from_str(&input)
-}
+    #[test]
+    fn test_parsing_steps_flag() {
+        assert_eq!(parse_args(std::iter::empty()).unwrap().steps, DEFAULT_STEPS);
+        let args = ["--steps", "6"].map(String::from);
+        assert_eq!(parse_args(args.into_iter()).unwrap().steps, 6);
+        assert!(parse_args(["--steps"].map(String::from).into_iter()).is_err());
+        assert!(parse_args(["--steps", "-1"].map(String::from).into_iter()).is_err());
+        let args = ["--show-distances"].map(String::from);
+        assert!(parse_args(args.into_iter()).unwrap().show_distances);
+    }
 
-fn points_from_here(point: &Point, puzzle_input: &PuzzleInput) -> Vec<Point> {
-    Direction::iter()
-        .map(|d| point.go(&d))
-        .filter(|p| {
-            p.x >= 0
-                && p.y >= 0
-                && p.x <= puzzle_input.max_x
-                && p.y <= puzzle_input.max_y
-                && puzzle_input.map.get(p).is_some_and(|t| !t.is_rock())
-        })
-        .collect()
-}
+    #[test]
+    fn test_parsing() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        assert_eq!((puzzle_input.width(), puzzle_input.height()), (11, 11));
+        assert_eq!(puzzle_input.start, Point { x: 5, y: 5 });
+        assert!(!puzzle_input.is_rock(5, 5));
+        assert!(!puzzle_input.is_rock(0, 0));
+        assert!(puzzle_input.is_rock(5, 1));
+        assert!(puzzle_input.is_rock(9, 5));
+        assert!(PuzzleInput::from_str("..S\n.x.").is_err());
+        assert!(PuzzleInput::from_str("..S\n....").is_err());
+    }
 
-const STEPS_TO_TAKE: u8 = 64;
+    #[test]
+    fn test_points_from_here() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let neighbours = Vec::from_iter(puzzle_input.points_from_here(puzzle_input.start));
+        assert_eq!(neighbours, [Point { x: 5, y: 4 }, Point { x: 4, y: 5 }]);
+        let corner = Vec::from_iter(puzzle_input.points_from_here(Point { x: 0, y: 0 }));
+        assert_eq!(corner, [Point { x: 0, y: 1 }, Point { x: 1, y: 0 }]);
+    }
+
+    #[test]
+    fn test_start_position() {
+        let error = PuzzleInput::from_str("...\n...").err().unwrap();
+        assert!(error.to_string().contains("Couldn't find"), "{error}");
 
-fn solve(puzzle_input: PuzzleInput) -> usize {
-    let mut points = HashSet::from([puzzle_input.start]);
-    for _ in 0..STEPS_TO_TAKE {
-        points = HashSet::from_iter(
-            points
-                .iter()
-                .flat_map(|p| points_from_here(p, &puzzle_input)),
-        )
+        let puzzle_input = PuzzleInput::from_str("...\n.S.").unwrap();
+        assert_eq!(puzzle_input.start, Point { x: 1, y: 1 });
+        assert!(!puzzle_input.is_rock(1, 1));
+
+        let error = PuzzleInput::from_str("S..\n.S.").err().unwrap().to_string();
+        assert!(error.contains("Point { x: 0, y: 0 }"), "{error}");
+        assert!(error.contains("Point { x: 1, y: 1 }"), "{error}");
     }
-    points.len()
-}
 
-fn main() {
-    let input = parse_input("input.txt").unwrap();
-    println!("{}", solve(input))
+    #[test]
+    fn test_distance_map() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let distance_map = puzzle_input.bfs_distances();
+        for (x, y, expected) in [
+            (5, 5, Some(0)),
+            (5, 4, Some(1)),
+            (3, 5, Some(2)),
+            (3, 4, Some(3)),
+            (0, 0, Some(10)),
+            (10, 10, Some(14)),
+            (6, 5, None),
+        ] {
+            assert_eq!(distance_map.get(Point { x, y }), expected, "({x}, {y})");
+        }
+        assert_eq!(distance_map.max_distance(), 14);
+        assert_eq!(distance_map.count_within(6, Parity::Even), 16);
+        let rendered = puzzle_input.render_distances(&distance_map);
+        assert_eq!(rendered.lines().nth(5), Some("7##210####9"));
+    }
 }
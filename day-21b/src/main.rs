@@ -0,0 +1,272 @@
+use anyhow::{bail, Context, Result};
+use day_21a::{parse_input, PuzzleInput, TiledWalk};
+
+const DEFAULT_STEPS: u64 = 26_501_365;
+
+/// How many grid-widths' worth of steps to simulate before giving up on
+/// the step counts settling into a quadratic
+const MAX_PERIODS: u64 = 20;
+
+/// On grids shaped like the real input, the step counts are quadratic from the outset,
+/// so five samples are all that should be needed
+const MAX_PERIODS_FOR_VALID_INPUT: u64 = 4;
+
+struct Options {
+    /// How many steps the elf takes
+    steps: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            steps: DEFAULT_STEPS,
+        }
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--steps" => {
+                let Some(n) = args.next() else {
+                    bail!("Expected a number of steps after `--steps`")
+                };
+                options.steps = n
+                    .parse()
+                    .with_context(|| format!("Invalid number of steps: {n}"))?
+            }
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
+}
+
+/// Evaluate at `index` the quadratic `f` with `f(0) = first`
+/// and first and second differences `first_difference` and `second_difference`
+fn extrapolate(first: i128, first_difference: i128, second_difference: i128, index: i128) -> i128 {
+    first + index * first_difference + index * (index - 1) / 2 * second_difference
+}
+
+/// Check that the grid has the structure of the real puzzle input that makes
+/// the step counts grow quadratically from the outset: it's square, with the start
+/// at the exact centre, and with no rocks in the start row, the start column,
+/// or around the border.
+fn validate_for_extrapolation(puzzle_input: &PuzzleInput) -> Result<()> {
+    let (width, height) = (puzzle_input.width(), puzzle_input.height());
+    if width != height {
+        bail!("The grid isn't square: it's {width} wide and {height} high")
+    }
+    let start = puzzle_input.start;
+    if width % 2 == 0 || start.x != width / 2 || start.y != height / 2 {
+        bail!("The start {start:?} isn't at the exact centre of the {width}x{height} grid")
+    }
+    if let Some(x) = (0..width).find(|&x| puzzle_input.is_rock(x, start.y)) {
+        bail!("The start row (y = {}) contains a rock at x = {x}", start.y)
+    }
+    if let Some(y) = (0..height).find(|&y| puzzle_input.is_rock(start.x, y)) {
+        bail!(
+            "The start column (x = {}) contains a rock at y = {y}",
+            start.x
+        )
+    }
+    let border = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]));
+    for (x, y) in border {
+        if puzzle_input.is_rock(x, y) {
+            bail!("The border contains a rock at ({x}, {y})")
+        }
+    }
+    Ok(())
+}
+
+/// The number of plots reachable in exactly `steps` steps on the infinitely tiled map.
+///
+/// Once the walk has spread across enough copies of the grid, the number of plots
+/// reachable in `n`, `n + width`, `n + 2 * width`... steps grows quadratically.
+/// Simulate until five consecutive samples agree on a quadratic,
+/// then extrapolate from the last three.
+///
+/// For grids without the real input's structure (see `validate_for_extrapolation`),
+/// it can take many more samples for the counts to settle, so the walk is
+/// simulated directly for much longer before giving up.
+fn solve_part_b(puzzle_input: &PuzzleInput, steps: u64) -> Result<u64> {
+    let width = puzzle_input.width() as u64;
+    if puzzle_input.height() as u64 != width {
+        bail!(
+            "Expected a square grid, but the grid is {width} wide and {} high",
+            puzzle_input.height()
+        )
+    }
+    let max_periods = match validate_for_extrapolation(puzzle_input) {
+        Ok(()) => MAX_PERIODS_FOR_VALID_INPUT,
+        Err(problem) => {
+            eprintln!(
+                "{problem}, so extrapolating from the first few step counts won't work; \
+                simulating the walk directly for up to {MAX_PERIODS} periods instead"
+            );
+            MAX_PERIODS
+        }
+    };
+    let (remainder, target_index) = (steps % width, steps / width);
+    let mut walk = TiledWalk::new(puzzle_input);
+    let mut samples: Vec<i128> = vec![];
+    for index in 0..=max_periods.min(target_index) {
+        samples.push(walk.reachable_after(remainder + index * width) as i128);
+        if index == target_index {
+            return Ok(walk.reachable_after(steps));
+        }
+        let [.., a, b, c, d, e] = samples[..] else {
+            continue;
+        };
+        let second_differences = [a - 2 * b + c, b - 2 * c + d, c - 2 * d + e];
+        if second_differences
+            .iter()
+            .all(|&diff| diff == second_differences[0])
+        {
+            let answer = extrapolate(
+                c,
+                d - c,
+                second_differences[0],
+                (target_index - index + 2) as i128,
+            );
+            return Ok(answer.try_into()?);
+        }
+    }
+    bail!(
+        "The number of reachable plots after {remainder} + k * {width} steps \
+        didn't settle into a quadratic within {max_periods} periods: {samples:?}"
+    )
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input = parse_input("input.txt").unwrap();
+    println!("{}", solve_part_b(&input, options.steps).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use day_21a::TiledWalk;
+
+    use crate::{
+        extrapolate, parse_args, solve_part_b, validate_for_extrapolation, PuzzleInput,
+        DEFAULT_STEPS,
+    };
+
+    const EXAMPLE: &str = "\
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+
+    /// A small grid with the same structure as the real input
+    const INPUT_SHAPED_EXAMPLE: &str = "\
+.......
+.#...#.
+..#.#..
+...S...
+.#.....
+..#..#.
+.......";
+
+    #[test]
+    fn test_validate_for_extrapolation() {
+        let puzzle_input = PuzzleInput::from_str(INPUT_SHAPED_EXAMPLE).unwrap();
+        assert!(validate_for_extrapolation(&puzzle_input).is_ok());
+
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let error = validate_for_extrapolation(&puzzle_input).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "The start row (y = 5) contains a rock at x = 1"
+        );
+
+        for (grid, problem) in [
+            ("...\n.S.", "isn't square"),
+            ("....\n.S..\n....\n....", "isn't at the exact centre"),
+            ("...\nS..\n...", "isn't at the exact centre"),
+            (".#.\n.S.\n...", "start column"),
+            ("....#\n.....\n..S..\n.....\n.....", "border"),
+        ] {
+            let puzzle_input = PuzzleInput::from_str(grid).unwrap();
+            let error = validate_for_extrapolation(&puzzle_input).unwrap_err();
+            assert!(error.to_string().contains(problem), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_input_shaped_example() {
+        let puzzle_input = PuzzleInput::from_str(INPUT_SHAPED_EXAMPLE).unwrap();
+        let mut walk = TiledWalk::new(&puzzle_input);
+        for steps in [3, 50, 101, 200] {
+            assert_eq!(
+                solve_part_b(&puzzle_input, steps).unwrap(),
+                walk.reachable_after(steps),
+                "{steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // f(n) = n^2 + n + 1: 1, 3, 7, 13...
+        assert_eq!(extrapolate(1, 2, 2, 0), 1);
+        assert_eq!(extrapolate(1, 2, 2, 3), 13);
+        assert_eq!(extrapolate(1, 2, 2, 10), 111);
+    }
+
+    #[test]
+    fn test_direct_simulation() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let mut walk = TiledWalk::new(&puzzle_input);
+        for (steps, expected) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            assert_eq!(walk.reachable_after(steps), expected, "{steps} steps");
+        }
+    }
+
+    #[test]
+    fn test_example() {
+        let puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        for (steps, expected) in [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ] {
+            assert_eq!(
+                solve_part_b(&puzzle_input, steps).unwrap(),
+                expected,
+                "{steps} steps"
+            );
+        }
+    }
+
+    #[test]
+    fn test_non_square_grid() {
+        let puzzle_input = PuzzleInput::from_str("...\n.S.").unwrap();
+        let err = solve_part_b(&puzzle_input, 100).unwrap_err();
+        assert!(err.to_string().contains("square"), "{err}");
+    }
+
+    #[test]
+    fn test_parsing_steps_flag() {
+        assert_eq!(parse_args(std::iter::empty()).unwrap().steps, DEFAULT_STEPS);
+        let args = ["--steps", "500"].map(String::from);
+        assert_eq!(parse_args(args.into_iter()).unwrap().steps, 500);
+        assert!(parse_args(["--steps", "x"].map(String::from).into_iter()).is_err());
+    }
+}
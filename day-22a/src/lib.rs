@@ -0,0 +1,510 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct XYPoint {
+    pub x: u16,
+    pub y: u16,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Brick {
+    pub min_x: u16,
+    pub max_x: u16,
+    pub min_y: u16,
+    pub max_y: u16,
+    pub min_z: u16,
+    pub max_z: u16,
+}
+
+impl Brick {
+    pub fn z_range(&self) -> std::ops::RangeInclusive<u16> {
+        self.min_z..=self.max_z
+    }
+
+    fn fall_by_one(&mut self) {
+        self.min_z -= 1;
+        self.max_z -= 1;
+    }
+
+    pub fn xy_points(&self) -> impl Iterator<Item = XYPoint> + '_ {
+        (self.min_x..=self.max_x)
+            .flat_map(move |x| (self.min_y..=self.max_y).map(move |y| XYPoint { x, y }))
+    }
+
+    /// Whether the two bricks would overlap if seen from above,
+    /// i.e. whether one could ever land on the other
+    pub fn intersects_xy(&self, other: &Brick) -> bool {
+        self.min_x.max(other.min_x) <= self.max_x.min(other.max_x)
+            && self.min_y.max(other.min_y) <= self.max_y.min(other.max_y)
+    }
+}
+
+impl FromStr for Brick {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((left, right)) = s.split_once('~') else {
+            bail!("Expected a brick to have two ends separated by '~', got {s:?}")
+        };
+        let parse_end = |end: &str| -> Result<[u16; 3]> {
+            let coordinates = Result::<Vec<u16>, _>::from_iter(end.split(',').map(str::parse))?;
+            let Ok(coordinates) = coordinates.try_into() else {
+                bail!("Expected three coordinates for each end of a brick, got {end:?}")
+            };
+            Ok(coordinates)
+        };
+        let [x0, y0, z0] = parse_end(left)?;
+        let [x1, y1, z1] = parse_end(right)?;
+        let axes_varied = [x0 != x1, y0 != y1, z0 != z1]
+            .into_iter()
+            .filter(|&b| b)
+            .count();
+        if axes_varied > 1 {
+            bail!("Expected a brick to be a straight line along one axis, got {s:?}")
+        }
+        if z0 == 0 || z1 == 0 {
+            bail!("Expected every part of a brick to be above the ground (z >= 1), got {s:?}")
+        }
+        Ok(Brick {
+            min_x: x0.min(x1),
+            max_x: x0.max(x1),
+            min_y: y0.min(y1),
+            max_y: y0.max(y1),
+            min_z: z0.min(z1),
+            max_z: z0.max(z1),
+        })
+    }
+}
+
+/// A brick's index in `PuzzleInput::bricks`
+pub type BrickId = usize;
+
+/// The letter used for the brick in diagrams: `A` to `Z` for the first 26 bricks,
+/// and `?` for any beyond that
+fn brick_label(id: BrickId) -> char {
+    if id < 26 {
+        (b'A' + id as u8) as char
+    } else {
+        '?'
+    }
+}
+
+/// Which brick, if any, occupies each cell of the space the bricks fall through
+#[derive(Debug, Clone)]
+pub struct Occupancy {
+    cells: Vec<Option<BrickId>>,
+    width: usize,
+    depth: usize,
+}
+
+impl Occupancy {
+    /// Room for every x in `0..width`, y in `0..depth` and z in `0..height`
+    fn new(width: usize, depth: usize, height: usize) -> Self {
+        Occupancy {
+            cells: vec![None; width * depth * height],
+            width,
+            depth,
+        }
+    }
+
+    fn index(&self, x: u16, y: u16, z: u16) -> usize {
+        (z as usize) * (self.width * self.depth) + (y as usize) * self.width + (x as usize)
+    }
+
+    pub fn get(&self, x: u16, y: u16, z: u16) -> Option<BrickId> {
+        self.cells[self.index(x, y, z)]
+    }
+
+    fn set(&mut self, x: u16, y: u16, z: u16, id: BrickId) {
+        let index = self.index(x, y, z);
+        self.cells[index] = Some(id)
+    }
+
+    fn clear(&mut self, x: u16, y: u16, z: u16) {
+        let index = self.index(x, y, z);
+        self.cells[index] = None
+    }
+
+    #[cfg(test)]
+    fn occupied_cells(&self) -> impl Iterator<Item = (u16, u16, u16, BrickId)> + '_ {
+        let layer_size = self.width * self.depth;
+        self.cells
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, cell)| {
+                let (z, rest) = (index / layer_size, index % layer_size);
+                let (y, x) = (rest / self.width, rest % self.width);
+                cell.map(|id| (x as u16, y as u16, z as u16, id))
+            })
+    }
+}
+
+/// Which bricks each brick rests on, and which bricks rest on each brick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SupportGraph {
+    pub supported_by: Vec<HashSet<BrickId>>,
+    pub supports: Vec<HashSet<BrickId>>,
+}
+
+impl SupportGraph {
+    fn new(num_bricks: usize) -> Self {
+        SupportGraph {
+            supported_by: vec![HashSet::new(); num_bricks],
+            supports: vec![HashSet::new(); num_bricks],
+        }
+    }
+
+    /// Whether some brick rests on this brick and nothing else
+    pub fn is_sole_supporter(&self, id: BrickId) -> bool {
+        self.supports[id]
+            .iter()
+            .any(|&above| self.supported_by[above].len() == 1)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PuzzleInput {
+    pub bricks: Vec<Brick>,
+    pub occupancy: Occupancy,
+    /// Empty until the bricks have been settled
+    pub graph: SupportGraph,
+}
+
+impl PuzzleInput {
+    pub fn new(bricks: Vec<Brick>) -> Self {
+        let dimension = |coordinate: fn(&Brick) -> u16| {
+            bricks
+                .iter()
+                .map(coordinate)
+                .max()
+                .map_or(0, |max| max as usize + 1)
+        };
+        let mut occupancy = Occupancy::new(
+            dimension(|brick| brick.max_x),
+            dimension(|brick| brick.max_y),
+            dimension(|brick| brick.max_z),
+        );
+        for (id, brick) in bricks.iter().enumerate() {
+            for z in brick.z_range() {
+                for XYPoint { x, y } in brick.xy_points() {
+                    occupancy.set(x, y, z, id)
+                }
+            }
+        }
+        let graph = SupportGraph::new(bricks.len());
+        PuzzleInput {
+            bricks,
+            occupancy,
+            graph,
+        }
+    }
+
+    /// Let the brick fall until it lands on the ground or on other bricks,
+    /// returning the bricks it lands on
+    fn drop_brick(&mut self, id: BrickId) -> HashSet<BrickId> {
+        let brick = &mut self.bricks[id];
+        let occupancy = &mut self.occupancy;
+        while brick.min_z > 1 {
+            let z_below = brick.min_z - 1;
+            let bricks_below = HashSet::from_iter(
+                brick
+                    .xy_points()
+                    .filter_map(|XYPoint { x, y }| occupancy.get(x, y, z_below)),
+            );
+            if !bricks_below.is_empty() {
+                return bricks_below;
+            }
+            // Every layer of the brick has the same (x, y) footprint, so falling by one
+            // means occupying the layer below the bottom and vacating the top layer;
+            // the layers in between stay occupied by this brick
+            for XYPoint { x, y } in brick.xy_points() {
+                occupancy.set(x, y, z_below, id);
+                occupancy.clear(x, y, brick.max_z);
+            }
+            brick.fall_by_one();
+        }
+        HashSet::new()
+    }
+
+    /// Project the bricks onto the plane of `axis` and z, with `across` columns,
+    /// looking through `through` cells along the remaining axis
+    fn render_projection(
+        &self,
+        axis: char,
+        across: usize,
+        through: usize,
+        cell: impl Fn(u16, u16, u16) -> Option<BrickId>,
+    ) -> String {
+        let top = self
+            .bricks
+            .iter()
+            .map(|brick| brick.max_z)
+            .max()
+            .unwrap_or(0);
+        let mut lines = vec![
+            format!("{}{axis}", " ".repeat(across / 2)),
+            String::from_iter((0..across).map(|i| char::from_digit((i % 10) as u32, 10).unwrap())),
+        ];
+        for z in (1..=top).rev() {
+            let row = String::from_iter((0..across as u16).map(|a| {
+                let ids = HashSet::<BrickId>::from_iter(
+                    (0..through as u16).filter_map(|t| cell(a, t, z)),
+                );
+                match Vec::from_iter(ids)[..] {
+                    [] => '.',
+                    [id] => brick_label(id),
+                    _ => '#',
+                }
+            }));
+            lines.push(format!("{row} {z}"));
+        }
+        lines.push(format!("{} 0", "-".repeat(across)));
+        lines.join("\n")
+    }
+
+    /// The bricks as seen from the front, looking along the y axis
+    pub fn render_xz(&self) -> String {
+        let Occupancy { width, depth, .. } = self.occupancy;
+        self.render_projection('x', width, depth, |x, y, z| self.occupancy.get(x, y, z))
+    }
+
+    /// The bricks as seen from the side, looking along the x axis
+    pub fn render_yz(&self) -> String {
+        let Occupancy { width, depth, .. } = self.occupancy;
+        self.render_projection('y', depth, width, |y, x, z| self.occupancy.get(x, y, z))
+    }
+
+    /// Let every brick fall as far as it can, starting with the lowest,
+    /// and record which bricks end up resting on which.
+    /// Returns the settled bricks in the order they came to rest.
+    pub fn settle(&mut self) -> Vec<(BrickId, Brick)> {
+        let mut ids = Vec::from_iter(0..self.bricks.len());
+        // Bricks with the same min_z both occupy that layer, so their footprints
+        // can't overlap and neither can land on the other: their relative order is irrelevant
+        ids.sort_unstable_by_key(|&id| self.bricks[id].min_z);
+        for &id in &ids {
+            let bricks_below = self.drop_brick(id);
+            debug_assert!(bricks_below
+                .iter()
+                .all(|&below| self.bricks[below].intersects_xy(&self.bricks[id])));
+            for &below in &bricks_below {
+                self.graph.supports[below].insert(id);
+            }
+            self.graph.supported_by[id] = bricks_below;
+        }
+        Vec::from_iter(ids.into_iter().map(|id| (id, self.bricks[id].clone())))
+    }
+}
+
+impl FromStr for PuzzleInput {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let bricks = Result::<Vec<Brick>>::from_iter(s.lines().enumerate().map(|(i, line)| {
+            Brick::from_str(line).with_context(|| format!("Failed to parse line {}", i + 1))
+        }))?;
+        Ok(PuzzleInput::new(bricks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use crate::{brick_label, Brick, BrickId, PuzzleInput};
+
+    const EXAMPLE: &str = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[test]
+    fn test_brick_parsing() {
+        for line in EXAMPLE.lines() {
+            assert!(Brick::from_str(line).is_ok(), "{line}");
+        }
+        assert_eq!(
+            Brick::from_str("1,1,8~1,1,9").unwrap(),
+            Brick {
+                min_x: 1,
+                max_x: 1,
+                min_y: 1,
+                max_y: 1,
+                min_z: 8,
+                max_z: 9
+            }
+        );
+        let diagonal = Brick::from_str("0,0,2~2,2,2").unwrap_err();
+        assert!(diagonal.to_string().contains("0,0,2~2,2,2"), "{diagonal}");
+        let underground = Brick::from_str("0,0,0~0,2,0").unwrap_err();
+        assert!(underground.to_string().contains("z >= 1"), "{underground}");
+        assert!(Brick::from_str("0,0,1~0,2").is_err());
+        assert!(Brick::from_str("0,0,1").is_err());
+    }
+
+    #[test]
+    fn test_parse_error_names_line() {
+        let error = PuzzleInput::from_str("1,0,1~1,2,1\n0,0,2~2,2,2").unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse line 2");
+        assert!(format!("{error:#}").contains("straight line"), "{error:#}");
+    }
+
+    /// Every (x, y, z) cell recorded as occupied, and the brick occupying it
+    fn occupied_cells(puzzle_input: &PuzzleInput) -> HashSet<(u16, u16, u16, BrickId)> {
+        HashSet::from_iter(puzzle_input.occupancy.occupied_cells())
+    }
+
+    #[test]
+    fn test_vertical_brick_occupancy() {
+        let mut puzzle_input = PuzzleInput::from_str("0,0,5~0,0,7").unwrap();
+        puzzle_input.settle();
+        assert_eq!(puzzle_input.bricks[0].z_range(), 1..=3);
+        let expected = HashSet::from([(0, 0, 1, 0), (0, 0, 2, 0), (0, 0, 3, 0)]);
+        assert_eq!(occupied_cells(&puzzle_input), expected);
+    }
+
+    #[test]
+    fn test_brick_lands_on_fallen_vertical_brick() {
+        // If the vertical brick left anything behind at its original top (z = 7),
+        // the horizontal brick would come to rest at z = 8 instead of z = 4
+        let mut puzzle_input = PuzzleInput::from_str("0,0,5~0,0,7\n0,0,10~1,0,10").unwrap();
+        puzzle_input.settle();
+        let graph = &puzzle_input.graph;
+        assert_eq!(puzzle_input.bricks[1].z_range(), 4..=4);
+        assert_eq!(graph.supported_by[1], HashSet::from([0]));
+        assert_eq!(occupied_cells(&puzzle_input).len(), 5);
+    }
+
+    #[test]
+    fn test_settling_example() {
+        let mut puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        let settled = puzzle_input.settle();
+        let min_zs = Vec::from_iter(settled.iter().map(|(id, brick)| (*id, brick.min_z)));
+        assert_eq!(
+            min_zs,
+            [(0, 1), (1, 2), (2, 2), (3, 3), (4, 3), (5, 4), (6, 5)]
+        );
+        // The bricks stored in the puzzle input have fallen too,
+        // not just the ones returned from `settle()`
+        let stored = Vec::from_iter(puzzle_input.bricks.iter().map(|brick| brick.z_range()));
+        assert_eq!(stored, [1..=1, 2..=2, 2..=2, 3..=3, 3..=3, 4..=4, 5..=6]);
+    }
+
+    #[test]
+    fn test_rendering_example() {
+        let mut puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        // The puzzle marks cells covered by more than one brick with `?`,
+        // which is used here for bricks beyond the 26th, so `#` is used instead
+        let xz_before = [
+            " x", "012", ".G. 9", ".G. 8", "... 7", "FFF 6", "..E 5", "D.. 4", "CCC 3", "BBB 2",
+            ".A. 1", "--- 0",
+        ]
+        .join("\n");
+        let yz_before = [
+            " y", "012", ".G. 9", ".G. 8", "... 7", ".F. 6", "EEE 5", "DDD 4", "..C 3", "B.. 2",
+            "AAA 1", "--- 0",
+        ]
+        .join("\n");
+        assert_eq!(puzzle_input.render_xz(), xz_before);
+        assert_eq!(puzzle_input.render_yz(), yz_before);
+
+        puzzle_input.settle();
+        let xz_after = [
+            " x", "012", ".G. 6", ".G. 5", "FFF 4", "D.E 3", "### 2", ".A. 1", "--- 0",
+        ]
+        .join("\n");
+        let yz_after = [
+            " y", "012", ".G. 6", ".G. 5", ".F. 4", "### 3", "B.C 2", "AAA 1", "--- 0",
+        ]
+        .join("\n");
+        assert_eq!(puzzle_input.render_xz(), xz_after);
+        assert_eq!(puzzle_input.render_yz(), yz_after);
+    }
+
+    #[test]
+    fn test_brick_labels() {
+        assert_eq!(brick_label(0), 'A');
+        assert_eq!(brick_label(25), 'Z');
+        assert_eq!(brick_label(26), '?');
+    }
+
+    #[test]
+    fn test_bricks_on_the_ground() {
+        // Two single-cube bricks on the ground, jointly supporting a bar above them
+        let mut puzzle_input =
+            PuzzleInput::from_str("0,0,1~0,0,1\n1,0,1~1,0,1\n0,0,2~1,0,2").unwrap();
+        puzzle_input.settle();
+        let graph = &puzzle_input.graph;
+        assert!(graph.supported_by[0].is_empty());
+        assert!(graph.supported_by[1].is_empty());
+        assert_eq!(graph.supported_by[2], HashSet::from([0, 1]));
+        assert!(!graph.is_sole_supporter(0));
+        assert!(!graph.is_sole_supporter(1));
+        assert!(!graph.is_sole_supporter(2));
+    }
+
+    #[test]
+    fn test_intersects_xy() {
+        let brick = |s| Brick::from_str(s).unwrap();
+        let bricks = Vec::from_iter(EXAMPLE.lines().map(brick));
+        // A lies across B and C, which are side by side, as are D and E
+        assert!(bricks[0].intersects_xy(&bricks[1]));
+        assert!(bricks[0].intersects_xy(&bricks[2]));
+        assert!(!bricks[1].intersects_xy(&bricks[2]));
+        assert!(!bricks[3].intersects_xy(&bricks[4]));
+        // Crossing in the middle
+        assert!(bricks[3].intersects_xy(&bricks[5]));
+        // G is a vertical brick, one cell across, above the middle of A
+        assert!(bricks[6].intersects_xy(&bricks[0]));
+        assert!(!bricks[6].intersects_xy(&bricks[1]));
+        // Overlapping only at a corner, or only in z, or just missing
+        assert!(brick("0,0,1~0,2,1").intersects_xy(&brick("0,2,5~3,2,5")));
+        assert!(brick("0,0,1~0,0,5").intersects_xy(&brick("0,0,9~0,0,9")));
+        assert!(!brick("0,0,1~0,2,1").intersects_xy(&brick("1,0,1~1,2,1")));
+        assert!(!brick("0,0,1~0,2,1").intersects_xy(&brick("0,3,1~2,3,1")));
+        for (a, b) in [(0, 1), (1, 2), (3, 5), (6, 1)] {
+            assert_eq!(
+                bricks[a].intersects_xy(&bricks[b]),
+                bricks[b].intersects_xy(&bricks[a])
+            );
+        }
+    }
+
+    #[test]
+    fn test_support_graph() {
+        let mut puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        puzzle_input.settle();
+        let graph = &puzzle_input.graph;
+        // Bricks A to G are 0 to 6
+        let supports = [
+            vec![1, 2],
+            vec![3, 4],
+            vec![3, 4],
+            vec![5],
+            vec![5],
+            vec![6],
+            vec![],
+        ];
+        let supported_by = [
+            vec![],
+            vec![0],
+            vec![0],
+            vec![1, 2],
+            vec![1, 2],
+            vec![3, 4],
+            vec![5],
+        ];
+        assert_eq!(graph.supports, supports.map(HashSet::from_iter));
+        assert_eq!(graph.supported_by, supported_by.map(HashSet::from_iter));
+        let sole_supporters = Vec::from_iter((0..7).filter(|&id| graph.is_sole_supporter(id)));
+        assert_eq!(sole_supporters, [0, 5]);
+    }
+}
@@ -0,0 +1,72 @@
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use day_22a::PuzzleInput;
+
+#[derive(Default)]
+struct Options {
+    /// Print the settled pile of bricks from the front and the side
+    show: bool,
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    for arg in args {
+        match arg.as_str() {
+            "--show" => options.show = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
+}
+
+fn solve_part_a(input: &str, options: &Options) -> Result<usize> {
+    let mut puzzle_input = PuzzleInput::from_str(input)?;
+    puzzle_input.settle();
+    if options.show {
+        eprintln!(
+            "{}\n\n{}",
+            puzzle_input.render_xz(),
+            puzzle_input.render_yz()
+        );
+    }
+    Ok((0..puzzle_input.bricks.len())
+        .filter(|&id| !puzzle_input.graph.is_sole_supporter(id))
+        .count())
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    println!("{}", solve_part_a(&input, &options).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_args, solve_part_a, Options};
+
+    #[test]
+    fn test_example() {
+        let example = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+        assert_eq!(solve_part_a(example, &Options::default()).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_parsing_show_flag() {
+        assert!(!parse_args(std::iter::empty()).unwrap().show);
+        assert!(
+            parse_args(["--show"].map(String::from).into_iter())
+                .unwrap()
+                .show
+        );
+        assert!(parse_args(["--shw"].map(String::from).into_iter()).is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::{HashSet, VecDeque};
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+use anyhow::Result;
+use day_22a::{BrickId, PuzzleInput, SupportGraph};
+
+/// The number of other bricks that would fall if this one were disintegrated.
+///
+/// A brick falls once every brick supporting it has fallen, so it's checked
+/// each time one of its supporters falls; the last of them to fall
+/// is the one that brings it down.
+fn chain_reaction(graph: &SupportGraph, disintegrated: BrickId) -> usize {
+    let mut fallen = HashSet::from([disintegrated]);
+    let mut queue = VecDeque::from([disintegrated]);
+    while let Some(id) = queue.pop_front() {
+        for &above in &graph.supports[id] {
+            if !fallen.contains(&above) && graph.supported_by[above].is_subset(&fallen) {
+                fallen.insert(above);
+                queue.push_back(above);
+            }
+        }
+    }
+    fallen.len() - 1
+}
+
+fn solve_part_b(input: &str) -> Result<usize> {
+    let mut puzzle_input = PuzzleInput::from_str(input)?;
+    puzzle_input.settle();
+    Ok((0..puzzle_input.bricks.len())
+        .map(|id| chain_reaction(&puzzle_input.graph, id))
+        .sum())
+}
+
+fn main() {
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    println!("{}", solve_part_b(&input).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fs::read_to_string;
+    use std::str::FromStr;
+
+    use day_22a::{Brick, PuzzleInput};
+
+    use crate::{chain_reaction, solve_part_b};
+
+    const EXAMPLE: &str = "\
+1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    /// Remove the brick from the settled pile, let everything settle again,
+    /// and count how many bricks moved
+    fn count_falls_by_resettling(settled: &PuzzleInput, disintegrated: usize) -> usize {
+        let mut remaining = settled.bricks.clone();
+        remaining.remove(disintegrated);
+        let mut resettled = PuzzleInput::new(remaining.clone());
+        resettled.settle();
+        remaining
+            .iter()
+            .zip(&resettled.bricks)
+            .filter(|(before, after)| before != after)
+            .count()
+    }
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve_part_b(EXAMPLE).unwrap(), 7);
+    }
+
+    #[test]
+    fn test_chain_reactions_match_resettling() {
+        let mut puzzle_input = PuzzleInput::from_str(EXAMPLE).unwrap();
+        puzzle_input.settle();
+        let graph = &puzzle_input.graph;
+        let chain_reactions = Vec::from_iter((0..7).map(|id| chain_reaction(graph, id)));
+        assert_eq!(chain_reactions, [6, 0, 0, 0, 0, 1, 0]);
+        for (id, falls) in chain_reactions.into_iter().enumerate() {
+            assert_eq!(
+                falls,
+                count_falls_by_resettling(&puzzle_input, id),
+                "Brick {id}"
+            );
+        }
+    }
+
+    /// A deterministic Fisher-Yates shuffle, driven by a xorshift generator
+    fn shuffle<T>(items: &mut [T], mut seed: u64) {
+        for i in (1..items.len()).rev() {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            items.swap(i, (seed % (i as u64 + 1)) as usize);
+        }
+    }
+
+    /// Everything about the settled pile, described in terms of the input lines
+    /// rather than brick IDs, so that different orderings of the input can be compared
+    #[derive(Debug, PartialEq)]
+    struct Outcome<'a> {
+        settled: BTreeMap<&'a str, Brick>,
+        supports: BTreeSet<(&'a str, &'a str)>,
+        part_a: usize,
+        part_b: usize,
+    }
+
+    fn settle_lines<'a>(lines: &[&'a str]) -> Outcome<'a> {
+        let mut puzzle_input = PuzzleInput::from_str(&lines.join("\n")).unwrap();
+        puzzle_input.settle();
+        let graph = &puzzle_input.graph;
+        Outcome {
+            settled: BTreeMap::from_iter(lines.iter().copied().zip(puzzle_input.bricks.clone())),
+            supports: BTreeSet::from_iter(graph.supports.iter().enumerate().flat_map(
+                |(below, above)| above.iter().map(move |&above| (lines[below], lines[above])),
+            )),
+            part_a: (0..lines.len())
+                .filter(|&id| !graph.is_sole_supporter(id))
+                .count(),
+            part_b: (0..lines.len()).map(|id| chain_reaction(graph, id)).sum(),
+        }
+    }
+
+    #[test]
+    fn test_settling_is_independent_of_input_order() {
+        let real_input = read_to_string("input.txt").unwrap();
+        for input in [EXAMPLE, &real_input] {
+            let mut lines = Vec::from_iter(input.lines());
+            let expected = settle_lines(&lines);
+            for seed in 1..=5 {
+                shuffle(&mut lines, seed);
+                assert_eq!(settle_lines(&lines), expected, "Seed {seed}");
+            }
+        }
+    }
+}
@@ -0,0 +1,1026 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::fs::read_to_string;
+use std::hash::Hash;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use anyhow::{bail, Context, Result};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use strum::IntoEnumIterator;
+use strum_macros::{EnumIs, EnumIter};
+
+#[derive(Debug, Hash, PartialEq, Eq, EnumIter, Clone, Copy)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    pub fn all() -> HashSet<Direction> {
+        HashSet::from_iter(Direction::iter())
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, EnumIs)]
+pub enum Tile {
+    Path,
+    Forest,
+    Slope(Direction),
+}
+
+impl Tile {
+    /// The directions in which the hiker could leave this tile
+    /// (none at all if it's a forest tile, since they shouldn't be there)
+    pub fn available_directions(&self) -> HashSet<Direction> {
+        match self {
+            Tile::Path => Direction::all(),
+            Tile::Slope(direction) => HashSet::from([*direction]),
+            Tile::Forest => HashSet::new(),
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::Path => '.',
+            Self::Forest => '#',
+            Self::Slope(Direction::Down) => 'v',
+            Self::Slope(Direction::Up) => '^',
+            Self::Slope(Direction::Left) => '<',
+            Self::Slope(Direction::Right) => '>',
+        }
+    }
+}
+
+impl TryFrom<&char> for Tile {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &char) -> Result<Self> {
+        match s {
+            '.' => Ok(Self::Path),
+            '#' => Ok(Self::Forest),
+            '^' => Ok(Self::Slope(Direction::Up)),
+            '>' => Ok(Self::Slope(Direction::Right)),
+            'v' => Ok(Self::Slope(Direction::Down)),
+            '<' => Ok(Self::Slope(Direction::Left)),
+            _ => bail!("Don't know what tile {s} is meant to be!"),
+        }
+    }
+}
+
+impl Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct Point {
+    pub x: i16,
+    pub y: i16,
+}
+
+impl Point {
+    pub fn new(x: i16, y: i16) -> Self {
+        Self { x, y }
+    }
+
+    pub fn go(&self, direction: &Direction) -> Point {
+        let Point { x, y } = *self;
+        match direction {
+            Direction::Up => Self { x, y: y - 1 },
+            Direction::Down => Self { x, y: y + 1 },
+            Direction::Left => Self { x: x - 1, y },
+            Direction::Right => Self { x: x + 1, y },
+        }
+    }
+
+    pub fn available_directions(&self, max_x: &i16, max_y: &i16) -> HashSet<Direction> {
+        let mut directions = Direction::all();
+        let Point { x, y } = self;
+        if x == &0 {
+            directions.remove(&Direction::Left);
+        } else if x == max_x {
+            directions.remove(&Direction::Right);
+        }
+        if y == &0 {
+            directions.remove(&Direction::Up);
+        } else if y == max_y {
+            directions.remove(&Direction::Down);
+        }
+        directions
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Point { x, y } = self;
+        write!(f, "({x}, {y})")
+    }
+}
+
+pub struct Grid {
+    pub map: HashMap<Point, Tile>,
+    pub max_x: i16,
+    pub max_y: i16,
+    pub end_point: Point,
+}
+
+impl Grid {
+    pub fn new(map: HashMap<Point, Tile>, max_x: i16, max_y: i16) -> Result<Self> {
+        let exits = Vec::from_iter(
+            (0..=max_x)
+                .map(|x| Point::new(x, max_y))
+                .filter(|point| map[point].is_path()),
+        );
+        let end_point = match exits[..] {
+            [end] => end,
+            [] => bail!("Couldn't find a path tile in the bottom row"),
+            _ => bail!(
+                "Expected exactly one path tile in the bottom row, found {}",
+                exits.len()
+            ),
+        };
+        Ok(Grid {
+            map,
+            max_x,
+            max_y,
+            end_point,
+        })
+    }
+
+    /// The single path tile in the top row
+    pub fn start_point(&self) -> Result<Point> {
+        let openings = Vec::from_iter(
+            (0..=self.max_x)
+                .map(|x| Point::new(x, 0))
+                .filter(|point| self.map[point].is_path()),
+        );
+        match openings[..] {
+            [start] => Ok(start),
+            [] => bail!("Couldn't find a path tile in the top row"),
+            _ => bail!(
+                "Expected exactly one path tile in the top row, found {}",
+                openings.len()
+            ),
+        }
+    }
+
+    /// The number of tiles in the grid
+    pub fn size(&self) -> usize {
+        ((self.max_x + 1) as usize) * ((self.max_y + 1) as usize)
+    }
+
+    /// A unique index for each point in the grid, in the range `0..self.size()`
+    pub fn index(&self, point: &Point) -> usize {
+        (point.y as usize) * ((self.max_x + 1) as usize) + (point.x as usize)
+    }
+
+    /// The non-forest tiles adjacent to `point` (and the direction of each),
+    /// regardless of which way any slopes point
+    pub fn neighbours(&self, point: &Point) -> Vec<(Direction, Point)> {
+        let available = point.available_directions(&self.max_x, &self.max_y);
+        Direction::iter()
+            .filter(|d| available.contains(d))
+            .map(|d| (d, point.go(&d)))
+            .filter(|(_, p)| !self.map[p].is_forest())
+            .collect()
+    }
+
+    /// The same grid, but with every slope replaced by a plain path tile,
+    /// so that it can be walked in any direction (as in part b)
+    pub fn into_undirected(mut self) -> Grid {
+        for tile in self.map.values_mut() {
+            if tile.is_slope() {
+                *tile = Tile::Path
+            }
+        }
+        self
+    }
+}
+
+impl Grid {
+    /// Render the grid with a route drawn on it, as in the puzzle description:
+    /// `S` where the route starts, and `O` on every tile it steps onto after that
+    pub fn render_with_route(&self, route: &[Point]) -> String {
+        let steps: HashSet<&Point> = HashSet::from_iter(route.iter().skip(1));
+        let mut rows = vec![];
+        for y in 0..=self.max_y {
+            let mut row = String::new();
+            for x in 0..=self.max_x {
+                let point = Point::new(x, y);
+                row.push(if route.first() == Some(&point) {
+                    'S'
+                } else if steps.contains(&point) {
+                    'O'
+                } else {
+                    self.map[&point].as_char()
+                })
+            }
+            rows.push(row)
+        }
+        rows.join("\n")
+    }
+}
+
+impl Display for Grid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut rows = vec![];
+        for y in 0..=self.max_y {
+            let mut row = String::new();
+            for x in 0..=self.max_x {
+                let point = Point::new(x, y);
+                let tile = &self.map[&point];
+                row.push(tile.as_char())
+            }
+            debug_assert_eq!(row.len(), ((self.max_x + 1) as usize));
+            rows.push(row)
+        }
+        debug_assert_eq!(rows.len(), ((self.max_y + 1) as usize));
+        write!(f, "{}", rows.join("\n"))
+    }
+}
+
+impl FromStr for Grid {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut map = HashMap::new();
+        let (mut max_x, mut max_y) = (0, 0);
+        for (y, line) in s.lines().enumerate() {
+            let y = y.try_into()?;
+            max_y = y;
+            let line_max_x = i16::try_from(line.chars().count())? - 1;
+            if y == 0 {
+                max_x = line_max_x
+            } else if line_max_x != max_x {
+                bail!(
+                    "Line {y} has {} tiles, but line 0 has {}",
+                    line_max_x + 1,
+                    max_x + 1
+                )
+            }
+            for (x, c) in line.chars().enumerate() {
+                let x = x.try_into()?;
+                let point = Point { x, y };
+                let tile =
+                    Tile::try_from(&c).with_context(|| format!("Invalid tile at {point}"))?;
+                map.insert(point, tile);
+            }
+        }
+        if max_y < 1 {
+            bail!("Expected the maze to have at least 2 rows")
+        }
+        Grid::new(map, max_x, max_y)
+    }
+}
+
+/// A corridor leading from one junction of a `ContractedGraph` to another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge {
+    pub to: usize,
+    /// The tiles stepped onto walking along the corridor,
+    /// ending with the junction at the other end
+    pub tiles: Vec<Point>,
+    /// Whether the corridor can be walked in this direction
+    /// if slopes can only be walked down (as in part a)
+    pub downhill: bool,
+}
+
+impl Edge {
+    pub fn length(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Whether a route that has visited the nodes in `visited` (a bitmask)
+    /// can carry on along this corridor
+    fn can_walk(&self, visited: u64, respect_slopes: bool) -> bool {
+        visited & (1 << self.to) == 0 && (self.downhill || !respect_slopes)
+    }
+}
+
+/// The maze, contracted to the points where the hiker has a choice of where to go
+/// (plus the start and end points). Each edge is weighted with the length of
+/// the corridor between the two junctions it connects.
+pub struct ContractedGraph {
+    /// The location of each node in the grid
+    nodes: Vec<Point>,
+    /// `edges[node]` lists the corridors leading away from `node`
+    edges: Vec<Vec<Edge>>,
+    /// The length of the longest edge leading into each node
+    longest_edge_into: Vec<usize>,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ContractedGraph {
+    pub fn from_grid(grid: &Grid) -> Result<Self> {
+        let mut nodes = vec![grid.start_point()?, grid.end_point];
+        nodes.extend(grid.map.iter().filter_map(|(point, tile)| {
+            (!tile.is_forest() && grid.neighbours(point).len() >= 3).then_some(*point)
+        }));
+        if nodes.len() > 64 {
+            bail!(
+                "Found {} junctions, but at most 64 are supported",
+                nodes.len()
+            )
+        }
+        let indices: HashMap<Point, usize> =
+            HashMap::from_iter(nodes.iter().enumerate().map(|(i, p)| (*p, i)));
+        let mut edges = vec![vec![]; nodes.len()];
+        for (node, junction) in nodes.iter().enumerate() {
+            for (direction, first_step) in grid.neighbours(junction) {
+                let mut downhill = grid.map[junction]
+                    .available_directions()
+                    .contains(&direction);
+                let (mut previous, mut current) = (*junction, first_step);
+                let mut tiles = vec![current];
+                // Follow the corridor until it reaches another junction or a dead end
+                loop {
+                    if let Some(&to) = indices.get(&current) {
+                        edges[node].push(Edge {
+                            to,
+                            tiles,
+                            downhill,
+                        });
+                        break;
+                    }
+                    let next = Vec::from_iter(
+                        grid.neighbours(&current)
+                            .into_iter()
+                            .filter(|(_, p)| p != &previous),
+                    );
+                    let [(direction, next)] = next[..] else {
+                        break;
+                    };
+                    downhill &= grid.map[&current]
+                        .available_directions()
+                        .contains(&direction);
+                    (previous, current) = (current, next);
+                    tiles.push(current);
+                }
+            }
+        }
+        let mut longest_edge_into = vec![0; nodes.len()];
+        for edge in edges.iter().flatten() {
+            longest_edge_into[edge.to] = longest_edge_into[edge.to].max(edge.length())
+        }
+        Ok(ContractedGraph {
+            nodes,
+            edges,
+            longest_edge_into,
+            start: 0,
+            end: 1,
+        })
+    }
+
+    /// The location in the grid of each node, indexed by node
+    pub fn nodes(&self) -> &[Point] {
+        &self.nodes
+    }
+
+    pub fn edges(&self, node: usize) -> &[Edge] {
+        &self.edges[node]
+    }
+
+    /// The length of the longest route from `start` to `end` that doesn't visit
+    /// any node twice, or `None` if there's no such route. If `respect_slopes`
+    /// is false, slopes can be walked in any direction (as in part b).
+    pub fn longest_path(&self, start: usize, end: usize, respect_slopes: bool) -> Option<usize> {
+        self.search(start, end, respect_slopes, true, false).longest
+    }
+
+    /// Search exhaustively for the longest route from `start` to `end`.
+    /// If `prune` is true, the search abandons any branch that can't possibly
+    /// lead to a longer route than the longest one found so far.
+    /// If `capture_route` is true, the result includes every tile along the longest route.
+    pub fn search(
+        &self,
+        start: usize,
+        end: usize,
+        respect_slopes: bool,
+        prune: bool,
+        capture_route: bool,
+    ) -> SearchResult {
+        let shared_longest = AtomicUsize::new(0);
+        let new_search = |route| PathSearch {
+            graph: self,
+            end,
+            respect_slopes,
+            prune,
+            route,
+            longest_route: None,
+            shared_longest: &shared_longest,
+            result: SearchResult {
+                longest: None,
+                expansions: 0,
+                route: None,
+            },
+        };
+        let mut result = SearchResult {
+            longest: None,
+            expansions: 0,
+            route: None,
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        let searches = {
+            let mut search = new_search(capture_route.then(Vec::new));
+            search.explore(start, 0, 0, self.remaining_length_bound(0));
+            [search]
+        };
+
+        // The branches of the search are independent (apart from the best route
+        // found so far, which they share for pruning), so they can run in parallel
+        #[cfg(feature = "parallel")]
+        let searches = {
+            let first = Task {
+                node: start,
+                length: 0,
+                visited: 0,
+                bound: self.remaining_length_bound(0),
+                route: vec![],
+            };
+            let (tasks, expansions) = self.split_search(first, end, respect_slopes);
+            result.expansions += expansions;
+            Vec::from_par_iter(tasks.into_par_iter().map(|task| {
+                let mut search = new_search(capture_route.then_some(task.route));
+                search.explore(task.node, task.length, task.visited, task.bound);
+                search
+            }))
+        };
+
+        let mut longest_route = None;
+        for search in searches {
+            result.expansions += search.result.expansions;
+            if search.result.longest > result.longest {
+                result.longest = search.result.longest;
+                longest_route = search.longest_route;
+            }
+        }
+        result.route = longest_route.map(|edges| {
+            let mut route = vec![self.nodes[start]];
+            route.extend(edges.iter().flat_map(|edge| &edge.tiles));
+            route
+        });
+        result
+    }
+
+    /// Split a search into tasks that can be carried out independently,
+    /// by exploring the first few levels of it breadth-first
+    /// until there are enough tasks to keep every thread busy.
+    /// Also returns the number of nodes expanded while doing so.
+    #[cfg(feature = "parallel")]
+    fn split_search<'a>(
+        &'a self,
+        first: Task<'a>,
+        end: usize,
+        respect_slopes: bool,
+    ) -> (Vec<Task<'a>>, usize) {
+        let target = 4 * rayon::current_num_threads();
+        let mut tasks = vec![first];
+        let mut expansions = 0;
+        while tasks.len() < target && tasks.iter().any(|task| task.node != end) {
+            let mut next_tasks = vec![];
+            for task in tasks {
+                if task.node == end {
+                    next_tasks.push(task);
+                    continue;
+                }
+                expansions += 1;
+                let visited = task.visited | (1 << task.node);
+                let bound = task.bound - self.longest_edge_into[task.node];
+                for edge in &self.edges[task.node] {
+                    if edge.can_walk(visited, respect_slopes) {
+                        let mut route = task.route.clone();
+                        route.push(edge);
+                        next_tasks.push(Task {
+                            node: edge.to,
+                            length: task.length + edge.length(),
+                            visited,
+                            bound,
+                            route,
+                        })
+                    }
+                }
+            }
+            tasks = next_tasks;
+        }
+        (tasks, expansions)
+    }
+
+    /// An upper bound on the length of any route onwards from the current node
+    /// that only passes through nodes not in `visited` (a bitmask that includes
+    /// the current node). Each node on such a route is reached along exactly one edge,
+    /// which can be no longer than the longest edge leading into that node.
+    pub fn remaining_length_bound(&self, visited: u64) -> usize {
+        self.longest_edge_into
+            .iter()
+            .enumerate()
+            .filter(|(node, _)| visited & (1 << node) == 0)
+            .map(|(_, length)| length)
+            .sum()
+    }
+}
+
+/// The outcome of searching a `ContractedGraph` for the longest route between two nodes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult {
+    pub longest: Option<usize>,
+    /// The number of nodes the search expanded along the way
+    pub expansions: usize,
+    /// Every tile along the longest route, if we asked for it
+    pub route: Option<Vec<Point>>,
+}
+
+/// A depth-first search for the longest route to `end`,
+/// keeping track of the longest route found so far
+struct PathSearch<'a> {
+    graph: &'a ContractedGraph,
+    end: usize,
+    respect_slopes: bool,
+    prune: bool,
+    /// The edges taken so far, if we want to know which route is the longest
+    /// as well as how long it is
+    route: Option<Vec<&'a Edge>>,
+    longest_route: Option<Vec<&'a Edge>>,
+    /// The length of the longest route found so far by this search or any other
+    /// running alongside it (0 until a route has been found)
+    shared_longest: &'a AtomicUsize,
+    result: SearchResult,
+}
+
+/// Part of a `PathSearch` that can be carried out independently:
+/// a route part way through the search, and the state needed to carry on along it
+#[cfg(feature = "parallel")]
+struct Task<'a> {
+    node: usize,
+    length: usize,
+    visited: u64,
+    bound: usize,
+    route: Vec<&'a Edge>,
+}
+
+impl<'a> PathSearch<'a> {
+    /// Explore every route onwards from `node`, having walked `length` steps
+    /// through the nodes in `visited` (a bitmask) to get there.
+    /// `bound` is the graph's `remaining_length_bound(visited)`,
+    /// passed down so that it can be updated incrementally.
+    fn explore(&mut self, node: usize, length: usize, visited: u64, bound: usize) {
+        self.result.expansions += 1;
+        if node == self.end {
+            if self.result.longest.is_none_or(|longest| length > longest) {
+                self.result.longest = Some(length);
+                self.longest_route = self.route.clone();
+                self.shared_longest.fetch_max(length, Ordering::Relaxed);
+            }
+            return;
+        }
+        let visited = visited | (1 << node);
+        let graph = self.graph;
+        let bound = bound - graph.longest_edge_into[node];
+        let longest = self.shared_longest.load(Ordering::Relaxed);
+        if self.prune && longest > 0 && length + bound <= longest {
+            return;
+        }
+        for edge in &graph.edges[node] {
+            if edge.can_walk(visited, self.respect_slopes) {
+                if let Some(route) = &mut self.route {
+                    route.push(edge)
+                }
+                self.explore(edge.to, length + edge.length(), visited, bound);
+                if let Some(route) = &mut self.route {
+                    route.pop();
+                }
+            }
+        }
+    }
+}
+
+pub const INPUT_FILENAME: &str = "input.txt";
+
+pub fn load_input() -> String {
+    read_to_string(INPUT_FILENAME).expect("Expected `input.txt` to exist as a file!")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+    use std::{collections::HashSet, str::FromStr};
+
+    use crate::{
+        load_input, ContractedGraph, Direction, Edge, Grid, PathSearch, Point, SearchResult, Tile,
+    };
+
+    const EXAMPLE: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+
+    #[test]
+    fn test_parsing_tile_roundtrip() {
+        let characters = ".#^>v<";
+        for character in characters.chars() {
+            let parsed = Tile::try_from(&character).unwrap();
+            let roundtripped = parsed.as_char();
+            assert_eq!(
+                roundtripped, character,
+                "Parsing {character} failed to roundtrip",
+            )
+        }
+    }
+
+    #[test]
+    fn test_parsing_input_file() {
+        let raw_input = load_input();
+        let map = Grid::from_str(&raw_input).unwrap().map;
+        let tiles_found: HashSet<&Tile> = HashSet::from_iter(map.values());
+        assert!(tiles_found.contains(&Tile::Forest));
+        assert!(tiles_found.contains(&Tile::Path));
+        assert!(tiles_found.contains(&Tile::Slope(Direction::Down)));
+        assert!(tiles_found.contains(&Tile::Slope(Direction::Right)));
+    }
+
+    #[test]
+    fn test_enum_iteration() {
+        assert_eq!(Direction::all().len(), 4)
+    }
+
+    #[test]
+    fn test_available_directions_of_point() {
+        let (max_x, max_y) = (100, 100);
+        let point1 = Point::new(0, 0);
+        let expected1 = HashSet::from([Direction::Down, Direction::Right]);
+        assert_eq!(point1.available_directions(&max_x, &max_y), expected1);
+
+        let point2 = Point::new(1, 0);
+        let expected2 = HashSet::from([Direction::Down, Direction::Left, Direction::Right]);
+        assert_eq!(point2.available_directions(&max_x, &max_y), expected2);
+
+        let point3 = Point::new(0, 1);
+        let expected3 = HashSet::from([Direction::Up, Direction::Down, Direction::Right]);
+        assert_eq!(point3.available_directions(&max_x, &max_y), expected3);
+
+        let point4 = Point::new(50, 50);
+        let expected4 = HashSet::from([
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]);
+        assert_eq!(point4.available_directions(&max_x, &max_y), expected4);
+
+        let point5 = Point::new(max_x - 1, max_y);
+        let expected5 = HashSet::from([Direction::Up, Direction::Left, Direction::Right]);
+        assert_eq!(point5.available_directions(&max_x, &max_y), expected5);
+
+        let point6 = Point::new(max_x, max_y - 1);
+        let expected6 = HashSet::from([Direction::Up, Direction::Down, Direction::Left]);
+        assert_eq!(point6.available_directions(&max_x, &max_y), expected6);
+
+        let point7 = Point::new(max_x, max_y);
+        let expected7 = HashSet::from([Direction::Up, Direction::Left]);
+        assert_eq!(point7.available_directions(&max_x, &max_y), expected7);
+    }
+
+    #[test]
+    fn test_available_directions_of_good_tiles() {
+        let expected1 = HashSet::from([
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]);
+        assert_eq!(Tile::Path.available_directions(), expected1);
+
+        assert_eq!(
+            Tile::Slope(Direction::Down).available_directions(),
+            HashSet::from([Direction::Down])
+        );
+    }
+
+    #[test]
+    fn test_available_directions_of_forest_tile() {
+        assert_eq!(Tile::Forest.available_directions(), HashSet::new());
+    }
+
+    #[test]
+    fn test_file_parsing_roundtrip() {
+        let raw_input = load_input().replace("\r\n", "\n");
+        let parsed = Grid::from_str(&raw_input).unwrap();
+        let formatted = format!("{parsed}");
+        assert_eq!(formatted.trim(), raw_input.trim(), "{formatted}")
+    }
+
+    #[test]
+    fn test_start() {
+        let raw_input = load_input();
+        let input = Grid::from_str(&raw_input).unwrap();
+        assert_eq!(input.start_point().unwrap(), Point::new(1, 0));
+
+        let grid = Grid::from_str("###.#\n#...#\n#.###").unwrap();
+        assert_eq!(grid.start_point().unwrap(), Point::new(3, 0));
+
+        let grid = Grid::from_str("#####\n#...#\n#.###").unwrap();
+        assert!(grid.start_point().is_err());
+
+        let grid = Grid::from_str("#.#.#\n#...#\n#.###").unwrap();
+        let error = grid.start_point().unwrap_err();
+        assert!(error.to_string().contains("found 2"), "{error}");
+    }
+
+    #[test]
+    fn test_end() {
+        let raw_input = load_input();
+        let input = Grid::from_str(&raw_input).unwrap();
+        assert_eq!(input.end_point, Point::new(input.max_x - 1, input.max_y));
+
+        let grid = Grid::from_str("#.###\n#...#\n##.##").unwrap();
+        assert_eq!(grid.end_point, Point::new(2, 2));
+
+        let error = Grid::from_str("#.###\n#...#\n#####").err().unwrap();
+        assert!(error.to_string().contains("bottom row"), "{error}");
+
+        let error = Grid::from_str("#.###\n#...#\n#.#.#").err().unwrap();
+        assert!(error.to_string().contains("found 2"), "{error}");
+    }
+
+    #[test]
+    fn test_into_undirected() {
+        let grid = Grid::from_str(EXAMPLE).unwrap().into_undirected();
+        assert!(!grid.map.values().any(Tile::is_slope));
+        assert_eq!(
+            grid.map.values().filter(|tile| tile.is_forest()).count(),
+            316
+        );
+        assert_eq!(grid.to_string(), EXAMPLE.replace(['^', '>', 'v', '<'], "."));
+    }
+
+    #[test]
+    fn test_contracted_graph() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        let nodes = graph.nodes();
+        assert_eq!(nodes.len(), 9);
+        assert_eq!(nodes[graph.start], Point::new(1, 0));
+        assert_eq!(nodes[graph.end], Point::new(21, 22));
+        let node_at = |x, y| nodes.iter().position(|p| p == &Point::new(x, y)).unwrap();
+
+        // Each corridor leading away from a node, as (node it leads to, length, downhill)
+        let corridors = |node| {
+            Vec::from_iter(
+                graph
+                    .edges(node)
+                    .iter()
+                    .map(|edge: &Edge| (edge.to, edge.length(), edge.downhill)),
+            )
+        };
+
+        // The first junction is 15 steps down the corridor from the start
+        let first_junction = node_at(3, 5);
+        assert_eq!(corridors(graph.start), [(first_junction, 15, true)]);
+        // ...but walking back towards the start means going up the `v` slope at (3, 4)
+        assert!(corridors(first_junction).contains(&(graph.start, 15, false)));
+        // The corridor between these two junctions has a `>` slope at either end
+        let (a, b) = (node_at(3, 5), node_at(11, 3));
+        assert!(corridors(a).contains(&(b, 22, true)));
+        assert!(corridors(b).contains(&(a, 22, false)));
+        // The last junction is 5 steps from the end
+        let last_junction = node_at(19, 19);
+        assert!(corridors(last_junction).contains(&(graph.end, 5, true)));
+        let last_corridor = graph
+            .edges(last_junction)
+            .iter()
+            .find(|edge| edge.to == graph.end)
+            .unwrap();
+        assert_eq!(
+            last_corridor.tiles,
+            [(19, 20), (19, 21), (20, 21), (21, 21), (21, 22)].map(|(x, y)| Point::new(x, y))
+        );
+    }
+
+    #[test]
+    fn test_contracted_graph_longest_path() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        assert_eq!(graph.longest_path(graph.start, graph.end, true), Some(94));
+        assert_eq!(graph.longest_path(graph.start, graph.end, false), Some(154));
+        assert_eq!(graph.longest_path(graph.end, graph.start, true), None);
+        assert_eq!(graph.longest_path(graph.end, graph.start, false), Some(154));
+    }
+
+    #[test]
+    fn test_remaining_length_bound() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        let everything = (1 << graph.nodes().len()) - 1;
+        assert_eq!(graph.remaining_length_bound(everything), 0);
+        // Only the last corridor (5 steps long) leads into the end
+        assert_eq!(
+            graph.remaining_length_bound(everything ^ (1 << graph.end)),
+            5
+        );
+        // The longest edges into each of the other nodes, read off the example
+        assert_eq!(
+            graph.remaining_length_bound(1 << graph.start),
+            5 + 38 + 38 + 10 + 30 + 24 + 30 + 22
+        );
+    }
+
+    #[test]
+    fn test_remaining_length_bound_is_never_too_small() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        for respect_slopes in [true, false] {
+            for node in 0..graph.nodes().len() {
+                for excluded in 0..graph.nodes().len() {
+                    if excluded == node || excluded == graph.end {
+                        continue;
+                    }
+                    let visited = (1 << node) | (1 << excluded);
+                    let mut search = PathSearch {
+                        graph: &graph,
+                        end: graph.end,
+                        respect_slopes,
+                        prune: false,
+                        route: None,
+                        longest_route: None,
+                        shared_longest: &AtomicUsize::new(0),
+                        result: SearchResult {
+                            longest: None,
+                            expansions: 0,
+                            route: None,
+                        },
+                    };
+                    let bound = graph.remaining_length_bound(1 << excluded);
+                    search.explore(node, 0, 1 << excluded, bound);
+                    if let Some(longest) = search.result.longest {
+                        assert!(
+                            longest <= graph.remaining_length_bound(visited),
+                            "{node} avoiding {excluded}: {longest}"
+                        )
+                    }
+                }
+            }
+        }
+    }
+
+    /// A random maze with `size` tiles along each side, generated by a xorshift
+    /// generator. Tiles at odd coordinates are paths; the walls between them
+    /// are randomly either forest, path or a slope pointing right or down.
+    fn random_maze(size: usize, mut seed: u64) -> String {
+        let mut rows = vec![];
+        for y in 0..size {
+            let mut row = String::new();
+            for x in 0..size {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                let tile = if (y == 0 && x == 1) || (y == size - 1 && x == size - 2) {
+                    '.'
+                } else if x == 0 || y == 0 || x == size - 1 || y == size - 1 {
+                    '#'
+                } else if x % 2 == 1 && y % 2 == 1 {
+                    '.'
+                } else if x % 2 == 0 && y % 2 == 0 {
+                    '#'
+                } else {
+                    match (seed % 20, x % 2) {
+                        (0..=6, _) => '#',
+                        (7..=8, 0) => '>',
+                        (7..=8, _) => 'v',
+                        _ => '.',
+                    }
+                };
+                row.push(tile)
+            }
+            rows.push(row)
+        }
+        rows.join("\n")
+    }
+
+    #[test]
+    fn test_pruning_gives_the_same_answers() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        for (respect_slopes, expected) in [(true, 94), (false, 154)] {
+            let pruned = graph.search(graph.start, graph.end, respect_slopes, true, false);
+            let unpruned = graph.search(graph.start, graph.end, respect_slopes, false, false);
+            assert_eq!(pruned.longest, Some(expected));
+            assert_eq!(unpruned.longest, Some(expected));
+            assert!(pruned.expansions <= unpruned.expansions);
+        }
+
+        let mut routes_found = 0;
+        for seed in 1..=200 {
+            let maze = random_maze(11, seed);
+            let grid = Grid::from_str(&maze).unwrap();
+            let graph = ContractedGraph::from_grid(&grid).unwrap();
+            for respect_slopes in [true, false] {
+                let pruned = graph.search(graph.start, graph.end, respect_slopes, true, false);
+                let unpruned = graph.search(graph.start, graph.end, respect_slopes, false, false);
+                assert_eq!(pruned.longest, unpruned.longest, "\n{maze}");
+                routes_found += pruned.longest.is_some() as usize;
+            }
+        }
+        assert!(routes_found > 100, "Only found {routes_found} routes");
+    }
+
+    #[test]
+    fn test_render_with_route() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let graph = ContractedGraph::from_grid(&grid).unwrap();
+        for (respect_slopes, expected) in [(true, 94), (false, 154)] {
+            let result = graph.search(graph.start, graph.end, respect_slopes, true, true);
+            let route = result.route.unwrap();
+            assert_eq!(route.len(), expected + 1);
+            let rendered = grid.render_with_route(&route);
+            assert_eq!(rendered.matches('O').count(), expected);
+            assert_eq!(rendered.matches('S').count(), 1);
+        }
+
+        // Spot checks against the diagram of the longest hike in part a
+        let route = graph.search(graph.start, graph.end, true, true, true).route;
+        let rendered = grid.render_with_route(&route.unwrap());
+        let rows = Vec::from_iter(rendered.lines());
+        assert_eq!(rows[0], "#S#####################");
+        assert_eq!(rows[3], "###OOOOO#OOO>.###.#.###");
+        assert_eq!(rows[13], "#...#.>.#...>OOO#O###O#");
+        assert_eq!(rows[22], "#####################O#");
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_search_matches_sequential_search() {
+        let mazes = Vec::from_iter(
+            std::iter::once(EXAMPLE.to_string()).chain((1..=50).map(|seed| random_maze(11, seed))),
+        );
+        for maze in mazes {
+            let grid = Grid::from_str(&maze).unwrap();
+            let graph = ContractedGraph::from_grid(&grid).unwrap();
+            for respect_slopes in [true, false] {
+                let mut sequential = PathSearch {
+                    graph: &graph,
+                    end: graph.end,
+                    respect_slopes,
+                    prune: true,
+                    route: None,
+                    longest_route: None,
+                    shared_longest: &AtomicUsize::new(0),
+                    result: SearchResult {
+                        longest: None,
+                        expansions: 0,
+                        route: None,
+                    },
+                };
+                sequential.explore(graph.start, 0, 0, graph.remaining_length_bound(0));
+                let parallel = graph.search(graph.start, graph.end, respect_slopes, true, true);
+                assert_eq!(parallel.longest, sequential.result.longest, "\n{maze}");
+                let route_length = parallel.route.map(|route| route.len() - 1);
+                assert_eq!(route_length, parallel.longest, "\n{maze}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_parsing_errors() {
+        let error = Grid::from_str("#.###\n#..#\n###.#").err().unwrap();
+        assert_eq!(error.to_string(), "Line 1 has 4 tiles, but line 0 has 5");
+
+        let error = Grid::from_str("#.###\n#.x.#\n###.#").err().unwrap();
+        assert_eq!(error.to_string(), "Invalid tile at (2, 1)");
+        assert_eq!(
+            error.root_cause().to_string(),
+            "Don't know what tile x is meant to be!"
+        );
+
+        let error = Grid::from_str("#.#").err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Expected the maze to have at least 2 rows"
+        );
+        assert!(Grid::from_str("").is_err());
+    }
+}
@@ -1,127 +1,12 @@
-use std::collections::{HashMap, HashSet};
-use std::fmt::Display;
-use std::fs::read_to_string;
-use std::hash::Hash;
+use std::collections::HashSet;
 use std::str::FromStr;
 
 use anyhow::{bail, Result};
-use strum::IntoEnumIterator;
-use strum_macros::{EnumIs, EnumIter};
+use day_23a::{load_input, ContractedGraph, Grid, Point};
 
-#[derive(Debug, Hash, PartialEq, Eq, EnumIter, Clone, Copy)]
-enum Direction {
-    Up,
-    Right,
-    Down,
-    Left,
-}
-
-impl Direction {
-    fn all() -> HashSet<Direction> {
-        HashSet::from_iter(Direction::iter())
-    }
-}
-
-#[derive(Debug, Hash, PartialEq, Eq, EnumIs)]
-enum Tile {
-    Path,
-    Forest,
-    Slope(Direction),
-}
-
-impl Tile {
-    fn available_directions(&self) -> HashSet<Direction> {
-        match self {
-            Tile::Path => Direction::all(),
-            Tile::Slope(direction) => HashSet::from([*direction]),
-            Tile::Forest => panic!("Looks like we accidentally stepped onto a `Forest` tile!"),
-        }
-    }
-
-    fn as_char(&self) -> char {
-        match self {
-            Self::Path => '.',
-            Self::Forest => '#',
-            Self::Slope(Direction::Down) => 'v',
-            Self::Slope(Direction::Up) => '^',
-            Self::Slope(Direction::Left) => '<',
-            Self::Slope(Direction::Right) => '>',
-        }
-    }
-}
-
-impl TryFrom<&char> for Tile {
-    type Error = anyhow::Error;
-
-    fn try_from(s: &char) -> Result<Self> {
-        match s {
-            '.' => Ok(Self::Path),
-            '#' => Ok(Self::Forest),
-            '^' => Ok(Self::Slope(Direction::Up)),
-            '>' => Ok(Self::Slope(Direction::Right)),
-            'v' => Ok(Self::Slope(Direction::Down)),
-            '<' => Ok(Self::Slope(Direction::Left)),
-            _ => bail!("Don't know what tile {s} is meant to be!"),
-        }
-    }
-}
-
-impl Display for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_char())
-    }
-}
-
-#[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
-struct Point {
-    x: i16,
-    y: i16,
-}
-
-impl Point {
-    fn new(x: i16, y: i16) -> Self {
-        Self { x, y }
-    }
-
-    fn go(&self, direction: &Direction) -> Point {
-        let Point { x, y } = *self;
-        match direction {
-            Direction::Up => Self { x, y: y - 1 },
-            Direction::Down => Self { x, y: y + 1 },
-            Direction::Left => Self { x: x - 1, y },
-            Direction::Right => Self { x: x + 1, y },
-        }
-    }
-
-    fn available_directions(&self, max_x: &i16, max_y: &i16) -> HashSet<Direction> {
-        let mut directions = Direction::all();
-        let Point { x, y } = self;
-        if x == &0 {
-            directions.remove(&Direction::Left);
-        } else if x == max_x {
-            directions.remove(&Direction::Right);
-        }
-        if y == &0 {
-            directions.remove(&Direction::Up);
-        } else if y == max_y {
-            directions.remove(&Direction::Down);
-        }
-        directions
-    }
-}
-
-impl Display for Point {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let Point { x, y } = self;
-        write!(f, "({x}, {y})")
-    }
-}
-
-fn possible_next_points(
-    point: &Point,
-    grid: &Grid,
-    route_so_far: &HashSet<Point>,
-) -> HashSet<Point> {
+/// The unvisited tiles the hiker could step onto next from `point`.
+/// If `point` is somehow a forest tile, it's treated as a dead end.
+fn possible_next_points(point: &Point, grid: &Grid, visited: &[bool]) -> HashSet<Point> {
     debug_assert_ne!(point, &grid.end_point);
     let tile = &grid.map[point];
     let available_directions_from_point = point.available_directions(&grid.max_x, &grid.max_y);
@@ -130,248 +15,356 @@ fn possible_next_points(
         available_directions_from_point
             .intersection(&available_directions_from_tile)
             .map(|direction| point.go(direction))
-            .filter(|point| !route_so_far.contains(point) && !grid.map[point].is_forest()),
+            .filter(|point| !visited[grid.index(point)] && !grid.map[point].is_forest()),
     )
 }
 
-struct Grid {
-    map: HashMap<Point, Tile>,
-    max_x: i16,
-    max_y: i16,
-    end_point: Point,
+/// A junction on the current route, along with the branches from it that
+/// are still to be explored
+struct Frame {
+    /// The points stepped onto since the previous junction,
+    /// to be stepped back off once every branch from here has been explored
+    corridor: Vec<Point>,
+    branches: Vec<Point>,
 }
 
-impl Grid {
-    fn new(map: HashMap<Point, Tile>, max_x: i16, max_y: i16) -> Self {
-        Grid {
-            map,
-            max_x,
-            max_y,
-            end_point: Point {
-                x: max_x - 1,
-                y: max_y,
-            },
-        }
-    }
+/// A depth-first search for the longest route to the end, which backtracks
+/// by unmarking the points it visited on the way back up.
+///
+/// The search keeps its own stack of junctions rather than recursing,
+/// so that mazes with very many junctions along a route can't overflow the call stack.
+struct RouteSearch<'a> {
+    grid: &'a Grid,
+    visited: Vec<bool>,
+    /// The number of steps taken on the current route
+    length: usize,
+    longest: Option<usize>,
+    /// The current route, if we want to know which route is the longest
+    /// as well as how long it is
+    route: Option<Vec<Point>>,
+    longest_route: Option<Vec<Point>>,
 }
 
-impl Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut rows = vec![];
-        for y in 0..=self.max_y {
-            let mut row = String::new();
-            for x in 0..=self.max_x {
-                let point = Point::new(x, y);
-                let tile = &self.map[&point];
-                row.push(tile.as_char())
-            }
-            debug_assert_eq!(row.len(), ((self.max_x + 1) as usize));
-            rows.push(row)
+impl<'a> RouteSearch<'a> {
+    fn new(grid: &'a Grid, start: Point, capture_route: bool) -> Self {
+        let mut visited = vec![false; grid.size()];
+        visited[grid.index(&start)] = true;
+        RouteSearch {
+            grid,
+            visited,
+            length: 0,
+            longest: None,
+            route: capture_route.then(|| vec![start]),
+            longest_route: None,
         }
-        debug_assert_eq!(rows.len(), ((self.max_y + 1) as usize));
-        write!(f, "{}", rows.join("\n"))
     }
-}
-
-impl FromStr for Grid {
-    type Err = anyhow::Error;
 
-    fn from_str(s: &str) -> Result<Self> {
-        let mut map = HashMap::new();
-        let (mut max_x, mut max_y) = (0, 0);
-        for (y, line) in s.lines().enumerate() {
-            let y = y.try_into()?;
-            max_y = y;
-            for (x, c) in line.chars().enumerate() {
-                let x = x.try_into()?;
-                max_x = x;
-                let point = Point { x, y };
-                let tile = Tile::try_from(&c)?;
-                map.insert(point, tile);
-            }
+    fn step(&mut self, point: Point) {
+        self.visited[self.grid.index(&point)] = true;
+        self.length += 1;
+        if let Some(route) = &mut self.route {
+            route.push(point)
         }
-        Ok(Grid::new(map, max_x, max_y))
     }
-}
 
-const START_POINT: Point = Point { x: 1, y: 0 };
+    fn step_back(&mut self, point: Point) {
+        self.visited[self.grid.index(&point)] = false;
+        self.length -= 1;
+        if let Some(route) = &mut self.route {
+            route.pop();
+        }
+    }
 
-fn longest_route_from(point: &Point, grid: &Grid, mut route: HashSet<Point>) -> HashSet<Point> {
-    let mut possibilities = possible_next_points(point, grid, &route);
-    while possibilities.len() == 1 {
-        let next_point = *possibilities.iter().next().unwrap();
-        if route.contains(&next_point) {
-            return HashSet::new();
+    /// Follow the corridor onwards from `point` (which has already been stepped onto)
+    /// until we reach the end, a dead end or a junction
+    fn walk(&mut self, point: Point, mut corridor: Vec<Point>) -> Frame {
+        let mut current = point;
+        loop {
+            if current == self.grid.end_point {
+                if self.longest.is_none_or(|longest| self.length > longest) {
+                    self.longest = Some(self.length);
+                    self.longest_route = self.route.clone();
+                }
+                return Frame {
+                    corridor,
+                    branches: vec![],
+                };
+            }
+            let possibilities = possible_next_points(&current, self.grid, &self.visited);
+            if possibilities.len() == 1 {
+                current = *possibilities.iter().next().unwrap();
+                self.step(current);
+                corridor.push(current);
+                continue;
+            }
+            return Frame {
+                corridor,
+                branches: Vec::from_iter(possibilities),
+            };
         }
-        route.insert(next_point);
-        if next_point == grid.end_point {
-            return route;
-        };
-        possibilities = possible_next_points(&next_point, grid, &route)
     }
-    let mut biggest_possibility = HashSet::new();
-    for possibility in possibilities {
-        let new_route = &route | &HashSet::from([possibility]);
-        let route_from_there = longest_route_from(&possibility, grid, new_route);
-        if route_from_there.len() > biggest_possibility.len() {
-            biggest_possibility = route_from_there;
+
+    /// Explore every route onwards from `start`, which has already been stepped onto
+    fn explore(&mut self, start: Point) {
+        let mut stack = vec![self.walk(start, vec![])];
+        while let Some(frame) = stack.last_mut() {
+            if let Some(branch) = frame.branches.pop() {
+                self.step(branch);
+                let next_frame = self.walk(branch, vec![branch]);
+                stack.push(next_frame);
+            } else {
+                let frame = stack.pop().unwrap();
+                for point in frame.corridor.into_iter().rev() {
+                    self.step_back(point)
+                }
+            }
         }
     }
-    biggest_possibility
 }
 
-fn solve(grid: Grid) -> usize {
-    longest_route_from(&START_POINT, &grid, HashSet::from([START_POINT])).len() - 1
+#[derive(Default)]
+struct Options {
+    /// Print the maze with the longest hike drawn on it
+    show: bool,
+    /// Search the graph of junctions rather than stepping from tile to tile.
+    /// This is much faster, but only works for mazes with at most 64 junctions.
+    contracted: bool,
+    /// Let the hiker walk up slopes as well as down them, as in part b.
+    /// Without `--contracted`, this is very slow for the puzzle input.
+    undirected: bool,
 }
 
-const INPUT_FILENAME: &str = "input.txt";
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    for arg in args {
+        match arg.as_str() {
+            "--show" => options.show = true,
+            "--contracted" => options.contracted = true,
+            "--undirected" => options.undirected = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
+}
 
-fn load_input() -> String {
-    read_to_string(INPUT_FILENAME).expect("Expected `input.txt` to exist as a file!")
+fn solve(grid: Grid, options: &Options) -> Result<usize> {
+    let grid = if options.undirected {
+        grid.into_undirected()
+    } else {
+        grid
+    };
+    let start = grid.start_point()?;
+    let (longest, route) = if options.contracted {
+        let graph = ContractedGraph::from_grid(&grid)?;
+        let search = graph.search(graph.start, graph.end, true, true, options.show);
+        (search.longest, search.route)
+    } else {
+        let mut search = RouteSearch::new(&grid, start, options.show);
+        search.explore(start);
+        (search.longest, search.longest_route)
+    };
+    let Some(longest) = longest else {
+        bail!("Couldn't find any route from {start} to {}", grid.end_point)
+    };
+    if let Some(route) = &route {
+        eprintln!("{}", grid.render_with_route(route))
+    }
+    Ok(longest)
 }
 
 fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
     let raw_input = load_input();
     let input = Grid::from_str(&raw_input).unwrap();
-    println!("{}", solve(input))
+    println!("{}", solve(input, &options).unwrap())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::{collections::HashSet, str::FromStr};
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    use day_23a::{load_input, Grid, Point};
 
-    use crate::{load_input, solve, Direction, Grid, Point, Tile, START_POINT};
+    use crate::{parse_args, possible_next_points, solve, Options, RouteSearch};
+
+    const EXAMPLE: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
 
     #[test]
-    fn test_parsing_tile_roundtrip() {
-        let characters = ".#^>v<";
-        for character in characters.chars() {
-            let parsed = Tile::try_from(&character).unwrap();
-            let roundtripped = parsed.as_char();
-            assert_eq!(
-                roundtripped, character,
-                "Parsing {character} failed to roundtrip",
-            )
-        }
+    fn test_example() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let answer = solve(grid, &Options::default()).unwrap();
+        assert_eq!(answer, 94)
     }
 
     #[test]
-    fn test_parsing_input_file() {
-        let raw_input = load_input();
-        let map = Grid::from_str(&raw_input).unwrap().map;
-        let tiles_found: HashSet<&Tile> = HashSet::from_iter(map.values());
-        assert!(tiles_found.contains(&Tile::Forest));
-        assert!(tiles_found.contains(&Tile::Path));
-        assert!(tiles_found.contains(&Tile::Slope(Direction::Down)));
-        assert!(tiles_found.contains(&Tile::Slope(Direction::Right)));
+    fn test_start_in_another_column() {
+        let grid = Grid::from_str("###.###\n#.....#\n#####.#").unwrap();
+        assert_eq!(grid.start_point().unwrap(), Point::new(3, 0));
+        assert_eq!(solve(grid, &Options::default()).unwrap(), 4)
     }
 
     #[test]
-    fn test_enum_iteration() {
-        assert_eq!(Direction::all().len(), 4)
+    fn test_end_in_the_middle_column() {
+        let grid = Grid::from_str("#.###\n#...#\n##.##").unwrap();
+        assert_eq!(solve(grid, &Options::default()).unwrap(), 3)
     }
 
     #[test]
-    fn test_available_directions_of_point() {
-        let (max_x, max_y) = (100, 100);
-        let point1 = Point::new(0, 0);
-        let expected1 = HashSet::from([Direction::Down, Direction::Right]);
-        assert_eq!(point1.available_directions(&max_x, &max_y), expected1);
-
-        let point2 = Point::new(1, 0);
-        let expected2 = HashSet::from([Direction::Down, Direction::Left, Direction::Right]);
-        assert_eq!(point2.available_directions(&max_x, &max_y), expected2);
-
-        let point3 = Point::new(0, 1);
-        let expected3 = HashSet::from([Direction::Up, Direction::Down, Direction::Right]);
-        assert_eq!(point3.available_directions(&max_x, &max_y), expected3);
-
-        let point4 = Point::new(50, 50);
-        let expected4 = HashSet::from([
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ]);
-        assert_eq!(point4.available_directions(&max_x, &max_y), expected4);
-
-        let point5 = Point::new(max_x - 1, max_y);
-        let expected5 = HashSet::from([Direction::Up, Direction::Left, Direction::Right]);
-        assert_eq!(point5.available_directions(&max_x, &max_y), expected5);
-
-        let point6 = Point::new(max_x, max_y - 1);
-        let expected6 = HashSet::from([Direction::Up, Direction::Down, Direction::Left]);
-        assert_eq!(point6.available_directions(&max_x, &max_y), expected6);
-
-        let point7 = Point::new(max_x, max_y);
-        let expected7 = HashSet::from([Direction::Up, Direction::Left]);
-        assert_eq!(point7.available_directions(&max_x, &max_y), expected7);
+    fn test_no_route() {
+        let grid = Grid::from_str("#.###\n#.#.#\n###.#").unwrap();
+        let error = solve(grid, &Options::default()).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Couldn't find any route from (1, 0) to (3, 2)"
+        );
     }
 
     #[test]
-    fn test_available_directions_of_good_tiles() {
-        let expected1 = HashSet::from([
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ]);
-        assert_eq!(Tile::Path.available_directions(), expected1);
+    fn test_possible_next_points() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let mut visited = vec![false; grid.size()];
+        let next = |point, visited: &[bool]| possible_next_points(&point, &grid, visited);
 
         assert_eq!(
-            Tile::Slope(Direction::Down).available_directions(),
-            HashSet::from([Direction::Down])
+            next(Point::new(3, 5), &visited),
+            HashSet::from([Point::new(3, 4), Point::new(4, 5), Point::new(3, 6)])
+        );
+        visited[grid.index(&Point::new(3, 4))] = true;
+        assert_eq!(
+            next(Point::new(3, 5), &visited),
+            HashSet::from([Point::new(4, 5), Point::new(3, 6)])
+        );
+        // Slopes can only be left in the direction they point
+        assert_eq!(
+            next(Point::new(4, 5), &visited),
+            HashSet::from([Point::new(5, 5)])
         );
+        assert_eq!(next(Point::new(0, 1), &visited), HashSet::new());
     }
 
     #[test]
-    #[should_panic]
-    fn test_available_directions_on_forest_tile_panics() {
-        Tile::Forest.available_directions();
+    fn test_deep_maze() {
+        // A long corridor with a dead-end alcove off every other tile,
+        // so the route to the end passes through 15,000 junctions
+        let junctions = 15_000;
+        let mut rows = vec!["#.##"];
+        for _ in 0..junctions {
+            rows.extend(["#..#", "#.##"])
+        }
+        let grid = Grid::from_str(&rows.join("\n")).unwrap();
+        let answer = std::thread::Builder::new()
+            .stack_size(1 << 20)
+            .spawn(move || solve(grid, &Options::default()).unwrap())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(answer, 2 * junctions)
     }
 
     #[test]
-    fn test_file_parsing_roundtrip() {
-        let raw_input = load_input().replace("\r\n", "\n");
-        let parsed = Grid::from_str(&raw_input).unwrap();
-        let formatted = format!("{parsed}");
-        assert_eq!(formatted.trim(), raw_input.trim(), "{formatted}")
+    fn test_input_answer() {
+        let grid = Grid::from_str(&load_input()).unwrap();
+        assert_eq!(solve(grid, &Options::default()).unwrap(), 2314)
     }
 
     #[test]
-    fn test_start() {
-        let raw_input = load_input();
-        let input = Grid::from_str(&raw_input).unwrap();
-        assert_eq!(input.map[&START_POINT], Tile::Path);
+    fn test_contracted_search() {
+        let contracted = Options {
+            contracted: true,
+            ..Options::default()
+        };
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        assert_eq!(solve(grid, &contracted).unwrap(), 94);
+        let grid = Grid::from_str(&load_input()).unwrap();
+        assert_eq!(solve(grid, &contracted).unwrap(), 2314);
+        for maze in [
+            "###.###\n#.....#\n#####.#",
+            "#.###\n#...#\n##.##",
+            "#.###\n#.#.#\n###.#",
+        ] {
+            let answer = |options| solve(Grid::from_str(maze).unwrap(), options).ok();
+            assert_eq!(answer(&contracted), answer(&Options::default()), "\n{maze}");
+        }
     }
 
     #[test]
-    fn test_example() {
-        let example = "#.#####################
-#.......#########...###
-#######.#########.#.###
-###.....#.>.>.###.#.###
-###v#####.#v#.###.#.###
-###.>...#.#.#.....#...#
-###v###.#.#.#########.#
-###...#.#.#.......#...#
-#####.#.#.#######.#.###
-#.....#.#.#.......#...#
-#.#####.#.#.#########v#
-#.#...#...#...###...>.#
-#.#.#v#######v###.###v#
-#...#.>.#...>.>.#.###.#
-#####v#.#.###v#.#.###.#
-#.....#...#...#.#.#...#
-#.#########.###.#.#.###
-#...###...#...#...#.###
-###.###.#.###v#####v###
-#...#...#.#.>.>.#.>.###
-#.###.###.#.###.#.#v###
-#.....###...###...#...#
-#####################.#";
-        let grid = Grid::from_str(example).unwrap();
-        let answer = solve(grid);
-        assert_eq!(answer, 94)
+    fn test_capturing_longest_route() {
+        let grid = Grid::from_str(EXAMPLE).unwrap();
+        let start = grid.start_point().unwrap();
+        let mut search = RouteSearch::new(&grid, start, true);
+        search.explore(start);
+        let route = search.longest_route.unwrap();
+        assert_eq!(route.len(), 95);
+        assert_eq!(route.first(), Some(&start));
+        assert_eq!(route.last(), Some(&grid.end_point));
+        for pair in route.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            assert_eq!((a.x - b.x).abs() + (a.y - b.y).abs(), 1, "{a} -> {b}");
+        }
+        let rendered = grid.render_with_route(&route);
+        assert_eq!(rendered.matches('O').count(), 94);
+        let rows = Vec::from_iter(rendered.lines());
+        assert_eq!(rows[0], "#S#####################");
+        assert_eq!(rows[11], "#.#...#OOO#OOO###OOOOO#");
+    }
+
+    #[test]
+    fn test_undirected() {
+        for contracted in [false, true] {
+            let options = |undirected| Options {
+                contracted,
+                undirected,
+                ..Options::default()
+            };
+            let answer = |undirected| solve(Grid::from_str(EXAMPLE).unwrap(), &options(undirected));
+            assert_eq!(answer(false).unwrap(), 94);
+            assert_eq!(answer(true).unwrap(), 154);
+        }
+        // Walking up the slope is the only way to reach the end here
+        let maze = "#.###\n#...#\n###^#\n###.#";
+        assert!(solve(Grid::from_str(maze).unwrap(), &Options::default()).is_err());
+        let undirected = Options {
+            undirected: true,
+            ..Options::default()
+        };
+        assert_eq!(
+            solve(Grid::from_str(maze).unwrap(), &undirected).unwrap(),
+            5
+        );
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(!parse(&[]).unwrap().show);
+        assert!(parse(&["--show"]).unwrap().show);
+        assert!(!parse(&["--show"]).unwrap().contracted);
+        assert!(parse(&["--contracted", "--show"]).unwrap().contracted);
+        assert!(!parse(&["--contracted"]).unwrap().undirected);
+        assert!(parse(&["--undirected", "--contracted"]).unwrap().undirected);
+        assert!(parse(&["--shwo"]).is_err());
     }
 }
@@ -0,0 +1,100 @@
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use day_23a::{load_input, ContractedGraph, Grid};
+
+struct Options {
+    /// Whether to abandon branches of the search
+    /// that can't lead to a longer route than the best so far
+    prune: bool,
+    /// Print the maze with the longest hike drawn on it
+    show: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            prune: true,
+            show: false,
+        }
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    for arg in args {
+        match arg.as_str() {
+            "--no-prune" => options.prune = false,
+            "--show" => options.show = true,
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    Ok(options)
+}
+
+fn solve_part_b(input: &str, options: &Options) -> Result<usize> {
+    let grid = Grid::from_str(input)?;
+    let graph = ContractedGraph::from_grid(&grid)?;
+    // Slopes are just paths in part b
+    let search = graph.search(graph.start, graph.end, false, options.prune, options.show);
+    let Some(answer) = search.longest else {
+        bail!("Couldn't find any route from the start to the end!")
+    };
+    if let Some(route) = &search.route {
+        eprintln!("{}", grid.render_with_route(route))
+    }
+    Ok(answer)
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    println!("{}", solve_part_b(&load_input(), &options).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_args, solve_part_b, Options};
+
+    #[test]
+    fn test_example() {
+        let example = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#";
+        assert_eq!(solve_part_b(example, &Options::default()).unwrap(), 154);
+        let options = Options {
+            prune: false,
+            ..Options::default()
+        };
+        assert_eq!(solve_part_b(example, &options).unwrap(), 154);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert!(parse(&[]).unwrap().prune);
+        assert!(!parse(&["--no-prune"]).unwrap().prune);
+        let options = parse(&["--show", "--no-prune"]).unwrap();
+        assert!(options.show && !options.prune);
+        assert!(parse(&["--prune"]).is_err());
+    }
+}
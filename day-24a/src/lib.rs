@@ -0,0 +1,915 @@
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Point {
+    pub fn lies_within(&self, area: &Area<f64>) -> bool {
+        area.contains(&self.x, &self.y)
+    }
+
+    /// The point a fraction `t` of the way from this point to `other`:
+    /// this point when `t` is 0, and `other` when `t` is 1
+    pub fn interpolate_to(&self, other: &Point, t: f64) -> Point {
+        Point {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+}
+
+/// A point whose coordinates are the exact fractions `x / denominator` and `y / denominator`,
+/// for when the rounding in [`Point`] isn't good enough
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RationalPoint {
+    pub x: i128,
+    pub y: i128,
+    /// Always positive
+    pub denominator: i128,
+}
+
+impl RationalPoint {
+    pub fn to_point(&self) -> Point {
+        Point {
+            x: self.x as f64 / self.denominator as f64,
+            y: self.y as f64 / self.denominator as f64,
+        }
+    }
+
+    /// Like [`Point::lies_within`], but without any rounding
+    pub fn lies_within(&self, area: &Area<i64>) -> bool {
+        let contains = |range: &RangeInclusive<i64>, numerator: i128| {
+            let (min, max) = (*range.start() as i128, *range.end() as i128);
+            min * self.denominator <= numerator && numerator <= max * self.denominator
+        };
+        contains(&area.x, self.x) && contains(&area.y, self.y)
+    }
+}
+
+fn whole_number(bound: f64) -> Result<i64> {
+    // Every float in this range is exactly representable as an i64
+    let limit = i64::MAX as f64;
+    if bound.fract() != 0.0 || !(-limit..limit).contains(&bound) {
+        bail!("Exact comparisons need the area's bounds to be whole numbers, got {bound}")
+    }
+    Ok(bound as i64)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Vector {
+    pub dx: i64,
+    pub dy: i64,
+}
+
+/// Split a comma-separated triple like `19, 13, 30` into its parts
+fn parse_triple(s: &str) -> Result<[i64; 3]> {
+    let [a, b, c] = Vec::from_iter(s.split(',').map(str::trim))[..] else {
+        bail!("Expected three comma-separated numbers in {s:?}")
+    };
+    Ok([a.parse()?, b.parse()?, c.parse()?])
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Point3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl FromStr for Point3 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [x, y, z] = parse_triple(s)?;
+        Ok(Point3 { x, y, z })
+    }
+}
+
+impl Display for Point3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Point3 { x, y, z } = self;
+        write!(f, "{x}, {y}, {z}")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Velocity3 {
+    pub dx: i64,
+    pub dy: i64,
+    pub dz: i64,
+}
+
+impl FromStr for Velocity3 {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let [dx, dy, dz] = parse_triple(s)?;
+        Ok(Velocity3 { dx, dy, dz })
+    }
+}
+
+impl Display for Velocity3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Velocity3 { dx, dy, dz } = self;
+        write!(f, "{dx}, {dy}, {dz}")
+    }
+}
+
+/// The rectangle of the x-y plane in which we're looking for crossings
+#[derive(Debug, Clone, PartialEq)]
+pub struct Area<T = f64> {
+    pub x: RangeInclusive<T>,
+    pub y: RangeInclusive<T>,
+}
+
+impl<T: Copy> Area<T> {
+    /// An area with the same bounds on both axes, like the one in the puzzle
+    pub const fn square(min: T, max: T) -> Self {
+        Area {
+            x: RangeInclusive::new(min, max),
+            y: RangeInclusive::new(min, max),
+        }
+    }
+}
+
+impl<T: PartialOrd> Area<T> {
+    pub fn contains(&self, x: &T, y: &T) -> bool {
+        self.x.contains(x) && self.y.contains(y)
+    }
+}
+
+impl From<Area<i64>> for Area<f64> {
+    fn from(area: Area<i64>) -> Self {
+        let convert = |range: RangeInclusive<i64>| *range.start() as f64..=*range.end() as f64;
+        Area {
+            x: convert(area.x),
+            y: convert(area.y),
+        }
+    }
+}
+
+impl TryFrom<&Area<f64>> for Area<i64> {
+    type Error = anyhow::Error;
+
+    /// Fails if any of the area's bounds isn't a whole number
+    fn try_from(area: &Area<f64>) -> Result<Self> {
+        let convert = |range: &RangeInclusive<f64>| -> Result<RangeInclusive<i64>> {
+            Ok(whole_number(*range.start())?..=whole_number(*range.end())?)
+        };
+        Ok(Area {
+            x: convert(&area.x)?,
+            y: convert(&area.y)?,
+        })
+    }
+}
+
+/// How close to zero the sine of the angle between two paths
+/// has to be for [`HailstoneTrajectory::relationship_to`] to treat them as parallel
+const PARALLEL_TOLERANCE: f64 = 1e-12;
+
+/// The position of a hailstone at time 0, and how far it moves each nanosecond
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HailstoneTrajectory {
+    pub position: Point3,
+    pub velocity: Velocity3,
+}
+
+/// Whose past the crossing point of two hailstones' paths lies in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InThePastFor {
+    This,
+    Other,
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineRelationship {
+    /// The paths will cross at `point`, which this hailstone reaches after `this_time`
+    /// nanoseconds and the other one after `other_time`
+    NonParallelAndIntersecting {
+        point: Point,
+        this_time: f64,
+        other_time: f64,
+    },
+    /// The paths crossed before time 0 for one or both hailstones
+    NonParallelAndIntersectedInThePast(InThePastFor),
+    Parallel,
+    /// Both hailstones travel along the same line, so there's no single
+    /// crossing point (this never happens with the puzzle input)
+    SameLine,
+}
+
+impl LineRelationship {
+    /// When each hailstone reaches the point where their paths will cross, if they will
+    pub fn intersection_times(&self) -> Option<(f64, f64)> {
+        match self {
+            LineRelationship::NonParallelAndIntersecting {
+                this_time,
+                other_time,
+                ..
+            } => Some((*this_time, *other_time)),
+            _ => None,
+        }
+    }
+}
+
+impl HailstoneTrajectory {
+    /// The hailstone's position at time 0, ignoring the z axis
+    pub fn xy_point(&self) -> Point {
+        Point {
+            x: self.position.x as f64,
+            y: self.position.y as f64,
+        }
+    }
+
+    /// How far the hailstone moves each nanosecond, ignoring the z axis
+    pub fn xy_vector(&self) -> Vector {
+        Vector {
+            dx: self.velocity.dx,
+            dy: self.velocity.dy,
+        }
+    }
+
+    /// The direction the hailstone moves in the x-y plane, as a unit vector,
+    /// or `(0.0, 0.0)` if it only moves along the z axis
+    pub fn normalized_direction(&self) -> (f64, f64) {
+        let Vector { dx, dy } = self.xy_vector();
+        let (dx, dy) = (dx as f64, dy as f64);
+        let length = dx.hypot(dy);
+        if length == 0.0 {
+            return (0.0, 0.0);
+        }
+        (dx / length, dy / length)
+    }
+
+    /// Find where (if anywhere) the paths of the two hailstones cross in the x-y plane.
+    ///
+    /// The paths cross where `p + t * v = q + s * u` (`p` and `q` being the known points,
+    /// `v` and `u` the vectors). Crossing both sides of that equation with `u` or `v`
+    /// eliminates one of the unknowns, so `t = (q - p) × u / (v × u)`
+    /// and `s = (q - p) × v / (v × u)`. Each hailstone reaches the crossing point
+    /// at its own time, and the crossing only counts if neither time is in the past.
+    pub fn relationship_to(&self, other: &HailstoneTrajectory) -> LineRelationship {
+        // The cross product of the two unit vectors is the sine of the angle between the
+        // paths, whatever their speeds or whichever way they're heading. For paths that
+        // aren't parallel it's at least `1 / (|v| * |u|)`, since the velocities are
+        // integers, which is far above the tolerance for any puzzle-sized velocity.
+        let (a, b) = (self.normalized_direction(), other.normalized_direction());
+        let crossing_times = if (a.0 * b.1 - a.1 * b.0).abs() < PARALLEL_TOLERANCE {
+            None
+        } else {
+            self.crossing_times(other)
+        };
+        let Some((this_time, other_time)) = crossing_times else {
+            // The paths are parallel; they're the same line if the gap between
+            // the two known points is parallel to them too
+            let (v, p, q) = (self.xy_vector(), self.xy_point(), other.xy_point());
+            return if (q.x - p.x) * v.dy as f64 == (q.y - p.y) * v.dx as f64 {
+                LineRelationship::SameLine
+            } else {
+                LineRelationship::Parallel
+            };
+        };
+        match (this_time < 0.0, other_time < 0.0) {
+            (true, true) => {
+                LineRelationship::NonParallelAndIntersectedInThePast(InThePastFor::Both)
+            }
+            (true, false) => {
+                LineRelationship::NonParallelAndIntersectedInThePast(InThePastFor::This)
+            }
+            (false, true) => {
+                LineRelationship::NonParallelAndIntersectedInThePast(InThePastFor::Other)
+            }
+            (false, false) => {
+                let (p, v) = (self.xy_point(), self.xy_vector());
+                LineRelationship::NonParallelAndIntersecting {
+                    point: Point {
+                        x: p.x + this_time * v.dx as f64,
+                        y: p.y + this_time * v.dy as f64,
+                    },
+                    this_time,
+                    other_time,
+                }
+            }
+        }
+    }
+
+    /// When this hailstone and the other one reach the point where their paths cross
+    /// (`t` and `s` in [`relationship_to`](Self::relationship_to)),
+    /// or `None` if the paths are parallel
+    pub fn crossing_times(&self, other: &HailstoneTrajectory) -> Option<(f64, f64)> {
+        let (v, u) = (self.xy_vector(), other.xy_vector());
+        let (p, q) = (self.xy_point(), other.xy_point());
+        let (gap_x, gap_y) = (q.x - p.x, q.y - p.y);
+        // The vectors are integers, so this is exact. Working with cross products
+        // rather than gradients means vertical paths need no special treatment.
+        let denominator = v.dx * u.dy - v.dy * u.dx;
+        if denominator == 0 {
+            return None;
+        }
+        let denominator = denominator as f64;
+        Some((
+            (gap_x * u.dy as f64 - gap_y * u.dx as f64) / denominator,
+            (gap_x * v.dy as f64 - gap_y * v.dx as f64) / denominator,
+        ))
+    }
+
+    /// Where the two hailstones' paths will cross in the x-y plane, if they cross in the
+    /// future for both of them, worked out exactly with the same cross products as
+    /// [`relationship_to`](Self::relationship_to). With puzzle-sized inputs the
+    /// numerators stay below 10^21, well within `i128`.
+    pub fn exact_crossing_with(&self, other: &HailstoneTrajectory) -> Option<RationalPoint> {
+        let (v, u) = (self.velocity, other.velocity);
+        let (vx, vy, ux, uy) = (v.dx as i128, v.dy as i128, u.dx as i128, u.dy as i128);
+        let (px, py) = (self.position.x as i128, self.position.y as i128);
+        let gap_x = other.position.x as i128 - px;
+        let gap_y = other.position.y as i128 - py;
+        let denominator = vx * uy - vy * ux;
+        if denominator == 0 {
+            return None;
+        }
+        let this_time = gap_x * uy - gap_y * ux;
+        let other_time = gap_x * vy - gap_y * vx;
+        // Both times share the denominator, so flip all three to make it positive
+        let sign = denominator.signum();
+        let (denominator, this_time, other_time) =
+            (denominator * sign, this_time * sign, other_time * sign);
+        if this_time < 0 || other_time < 0 {
+            return None;
+        }
+        Some(RationalPoint {
+            x: px * denominator + this_time * vx,
+            y: py * denominator + this_time * vy,
+            denominator,
+        })
+    }
+
+    /// The times at which this hailstone and the other one are closest to each other's
+    /// paths in all three dimensions, as `(this hailstone's time, other hailstone's time)`.
+    /// Either time may be in the past. If the paths cross, these are the times at which
+    /// each hailstone reaches the crossing point.
+    ///
+    /// The gap `w = (p + t * v) - (q + s * u)` is shortest when it's perpendicular to both
+    /// paths, i.e. `w · v = 0` and `w · u = 0`; solving those two equations gives `t` and `s`.
+    /// If the paths are parallel, every point along them is equally close to the other
+    /// path, so this hailstone's time is taken to be 0.
+    pub fn closest_approach_3d(&self, other: &HailstoneTrajectory) -> (f64, f64) {
+        let as_i128 = |[x, y, z]: [i64; 3]| [x as i128, y as i128, z as i128];
+        let (p, v) = (self.position, self.velocity);
+        let (q, u) = (other.position, other.velocity);
+        let (v, u) = (as_i128([v.dx, v.dy, v.dz]), as_i128([u.dx, u.dy, u.dz]));
+        let gap = as_i128([p.x - q.x, p.y - q.y, p.z - q.z]);
+        let dot = |a: [i128; 3], b: [i128; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        // With puzzle-sized inputs these products stay below 10^23, so they're all exact
+        let (vv, vu, uu) = (dot(v, v), dot(v, u), dot(u, u));
+        let (vw, uw) = (dot(v, gap), dot(u, gap));
+        let denominator = vv * uu - vu * vu;
+        if denominator != 0 {
+            let denominator = denominator as f64;
+            return (
+                (vu * uw - uu * vw) as f64 / denominator,
+                (vv * uw - vu * vw) as f64 / denominator,
+            );
+        }
+        match (vv, uu) {
+            (_, uu) if uu != 0 => (0.0, uw as f64 / uu as f64),
+            // The other hailstone isn't moving, so it's all down to this one
+            (vv, _) if vv != 0 => (-vw as f64 / vv as f64, 0.0),
+            // Neither hailstone is moving
+            _ => (0.0, 0.0),
+        }
+    }
+}
+
+impl FromStr for HailstoneTrajectory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((position, velocity)) = s.split_once('@') else {
+            bail!("Expected a hailstone to be described as `position @ velocity`, got {s:?}")
+        };
+        Ok(HailstoneTrajectory {
+            position: position.parse()?,
+            velocity: velocity.parse()?,
+        })
+    }
+}
+
+impl Display for HailstoneTrajectory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} @ {}", self.position, self.velocity)
+    }
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<HailstoneTrajectory>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse()
+                .with_context(|| format!("Failed to parse line {}", i + 1))
+        })
+        .collect()
+}
+
+/// How each pair of hailstones' paths relate to each other, by their indices,
+/// and whether that pair counts as crossing inside `area`
+pub fn pairwise_relationships(
+    hailstone_trajectories: &[HailstoneTrajectory],
+    area: &Area,
+) -> Vec<(usize, usize, LineRelationship, bool)> {
+    let mut relationships = vec![];
+    for (i, first) in hailstone_trajectories.iter().enumerate() {
+        for (j, second) in hailstone_trajectories.iter().enumerate().skip(i + 1) {
+            let relationship = first.relationship_to(second);
+            let counted = matches!(
+                relationship,
+                LineRelationship::NonParallelAndIntersecting { point, this_time, other_time }
+                    if this_time >= 0.0 && other_time >= 0.0 && point.lies_within(area)
+            );
+            relationships.push((i, j, relationship, counted))
+        }
+    }
+    relationships
+}
+
+/// The number of pairs of hailstones whose paths will cross inside `area`
+pub fn solve(hailstone_trajectories: &[HailstoneTrajectory], area: &Area) -> usize {
+    pairwise_relationships(hailstone_trajectories, area)
+        .iter()
+        .filter(|(_, _, _, counted)| *counted)
+        .count()
+}
+
+/// Like [`solve`], but immune to rounding errors.
+/// Fails if any of the area's bounds isn't a whole number.
+pub fn solve_exactly(hailstone_trajectories: &[HailstoneTrajectory], area: &Area) -> Result<usize> {
+    let area = Area::<i64>::try_from(area)?;
+    let mut crossings = 0;
+    for (i, first) in hailstone_trajectories.iter().enumerate() {
+        for second in &hailstone_trajectories[i + 1..] {
+            if let Some(point) = first.exact_crossing_with(second) {
+                crossings += point.lies_within(&area) as usize
+            }
+        }
+    }
+    Ok(crossings)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        pairwise_relationships, parse_input, solve, solve_exactly, Area, HailstoneTrajectory,
+        InThePastFor, LineRelationship, Point, Point3, RationalPoint, Vector, Velocity3,
+    };
+
+    const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    const EXAMPLE_AREA: Area = Area::square(7.0, 27.0);
+
+    #[test]
+    fn test_example() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        assert_eq!(solve(&hailstones, &EXAMPLE_AREA), 2);
+        assert_eq!(solve_exactly(&hailstones, &EXAMPLE_AREA).unwrap(), 2)
+    }
+
+    #[test]
+    fn test_puzzle_area() {
+        let hailstones = parse_input(
+            "\
+299999999999000, 299999999998000, 0 @ 1, 2, 0
+300000000003000, 300000000000000, 0 @ -3, 0, 0
+50000000000000, 100000000000000, 0 @ 1, 2, 0
+350000000000000, 350000000000000, 0 @ 1, 1, 0",
+        )
+        .unwrap();
+        // Only the first two cross inside the area, at (3e14, 3e14) 1000 nanoseconds in.
+        // The first and third are parallel, the second and third cross at (1.5e14, 3e14),
+        // and the fourth is moving away from where it would cross the others.
+        let area = Area::square(200_000_000_000_000.0, 400_000_000_000_000.0);
+        assert_eq!(solve(&hailstones, &area), 1);
+        assert_eq!(solve_exactly(&hailstones, &area).unwrap(), 1);
+        // The example bounds are nowhere near any of these crossings
+        assert_eq!(solve(&hailstones, &EXAMPLE_AREA), 0);
+    }
+
+    fn assert_near(point: Point, x: f64, y: f64) {
+        assert!((point.x - x).abs() < 1e-3, "{point:?}");
+        assert!((point.y - y).abs() < 1e-3, "{point:?}");
+    }
+
+    fn assert_crosses_at(relationship: LineRelationship, x: f64, y: f64) {
+        let LineRelationship::NonParallelAndIntersecting { point, .. } = relationship else {
+            panic!("Expected the paths to cross in the future, got {relationship:?}")
+        };
+        assert_near(point, x, y)
+    }
+
+    #[test]
+    fn test_pairwise_relationships() {
+        enum Expected {
+            CrossingAt(f64, f64),
+            InThePast(InThePastFor),
+            Parallel,
+        }
+        use Expected::*;
+        // The puzzle's commentary on each pair in the example, with A to E as 0 to 4
+        let table = [
+            (0, 1, CrossingAt(14.333, 15.333), true),
+            (0, 2, CrossingAt(11.667, 16.667), true),
+            (0, 3, CrossingAt(6.2, 19.4), false),
+            (0, 4, InThePast(InThePastFor::This), false),
+            (1, 2, Parallel, false),
+            (1, 3, CrossingAt(-6.0, -5.0), false),
+            (1, 4, InThePast(InThePastFor::Both), false),
+            (2, 3, CrossingAt(-2.0, 3.0), false),
+            (2, 4, InThePast(InThePastFor::Other), false),
+            (3, 4, InThePast(InThePastFor::Both), false),
+        ];
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let relationships = pairwise_relationships(&hailstones, &EXAMPLE_AREA);
+        assert_eq!(relationships.len(), table.len());
+        for ((i, j, relationship, counted), (expected_i, expected_j, expected, expected_counted)) in
+            relationships.into_iter().zip(table)
+        {
+            assert_eq!((i, j, counted), (expected_i, expected_j, expected_counted));
+            match expected {
+                CrossingAt(x, y) => assert_crosses_at(relationship, x, y),
+                InThePast(whose) => assert_eq!(
+                    relationship,
+                    LineRelationship::NonParallelAndIntersectedInThePast(whose)
+                ),
+                Parallel => assert_eq!(relationship, LineRelationship::Parallel),
+            }
+        }
+    }
+
+    #[test]
+    fn test_closest_approach_3d() {
+        let closest = |a: &str, b: &str| hailstone(a).closest_approach_3d(&hailstone(b));
+        // The rock from part b's example hits each hailstone: these paths really cross
+        let rock = "24, 13, 10 @ -3, 1, 2";
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        for (hailstone, time) in hailstones.iter().zip([5.0, 3.0, 4.0, 6.0, 1.0]) {
+            let (rock_time, hailstone_time) = closest(rock, &hailstone.to_string());
+            assert!((rock_time - time).abs() < 1e-9, "{hailstone}: {rock_time}");
+            assert!(
+                (hailstone_time - time).abs() < 1e-9,
+                "{hailstone}: {hailstone_time}"
+            );
+        }
+        // Skew paths: one along the x axis, the other parallel to the y axis and
+        // passing over the origin, which it's closest to 5ns before time 0
+        assert_eq!(
+            closest("0, 0, 0 @ 1, 0, 0", "0, 5, 1 @ 0, 1, 0"),
+            (0.0, -5.0)
+        );
+        assert_eq!(
+            closest("-3, 0, 0 @ 1, 0, 0", "0, 5, 1 @ 0, 1, 0"),
+            (3.0, -5.0)
+        );
+        // The answer's the same whichever way round the hailstones are
+        assert_eq!(
+            closest("0, 5, 1 @ 0, 1, 0", "-3, 0, 0 @ 1, 0, 0"),
+            (-5.0, 3.0)
+        );
+        // Parallel paths
+        assert_eq!(
+            closest("0, 0, 0 @ 1, 0, 0", "4, 1, 0 @ 2, 0, 0"),
+            (0.0, -2.0)
+        );
+        // Hailstones that aren't moving
+        assert_eq!(
+            closest("5, 5, 5 @ 0, 0, 0", "2, 0, 0 @ -1, 0, 0"),
+            (0.0, -3.0)
+        );
+        assert_eq!(
+            closest("2, 0, 0 @ -1, 0, 0", "5, 5, 5 @ 0, 0, 0"),
+            (-3.0, 0.0)
+        );
+        assert_eq!(
+            closest("1, 2, 3 @ 0, 0, 0", "4, 5, 6 @ 0, 0, 0"),
+            (0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_intersection_times() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let relationship = hailstones[0].relationship_to(&hailstones[1]);
+        let (this_time, other_time) = relationship.intersection_times().unwrap();
+        assert!((this_time - 7.0 / 3.0).abs() < 1e-9, "{this_time}");
+        assert!((other_time - 11.0 / 3.0).abs() < 1e-9, "{other_time}");
+        assert_eq!(
+            relationship.intersection_times(),
+            hailstones[0].crossing_times(&hailstones[1])
+        );
+        // Swapping the hailstones swaps the times
+        let swapped = hailstones[1].relationship_to(&hailstones[0]);
+        assert_eq!(swapped.intersection_times(), Some((other_time, this_time)));
+        // Crossings in the past, and parallel paths, don't have any
+        assert_eq!(
+            hailstones[0]
+                .relationship_to(&hailstones[4])
+                .intersection_times(),
+            None
+        );
+        assert_eq!(
+            hailstones[1]
+                .relationship_to(&hailstones[2])
+                .intersection_times(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_crossing_times() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        // A reaches (14.333, 15.333) after 2.333ns, and B after 3.667ns
+        let (this_time, other_time) = hailstones[0].crossing_times(&hailstones[1]).unwrap();
+        assert!((this_time - 7.0 / 3.0).abs() < 1e-9, "{this_time}");
+        assert!((other_time - 11.0 / 3.0).abs() < 1e-9, "{other_time}");
+        // Times in the past are negative
+        let (this_time, _) = hailstones[0].crossing_times(&hailstones[4]).unwrap();
+        assert!(this_time < 0.0);
+        assert_eq!(hailstones[1].crossing_times(&hailstones[2]), None);
+    }
+
+    #[test]
+    fn test_normalized_direction() {
+        let direction = |input| hailstone(input).normalized_direction();
+        assert_eq!(direction("0, 0, 0 @ 3, -4, 7"), (0.6, -0.8));
+        // Vertical and horizontal paths need no special treatment
+        assert_eq!(direction("0, 0, 0 @ 0, 5, 1"), (0.0, 1.0));
+        assert_eq!(direction("0, 0, 0 @ -2, 0, 1"), (-1.0, 0.0));
+        assert_eq!(direction("0, 0, 0 @ 0, 0, 1"), (0.0, 0.0));
+        // The second and third hailstones in the example travel parallel to each other
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let (a, b) = (
+            hailstones[1].normalized_direction(),
+            hailstones[2].normalized_direction(),
+        );
+        assert!((a.0 - b.0).abs() < 1e-12 && (a.1 - b.1).abs() < 1e-12);
+        for hailstone in hailstones {
+            let (dx, dy) = hailstone.normalized_direction();
+            assert!((dx.hypot(dy) - 1.0).abs() < 1e-12, "{hailstone}");
+        }
+    }
+
+    #[test]
+    fn test_lies_within() {
+        let point = |x, y| Point { x, y };
+        assert!(point(7.0, 27.0).lies_within(&EXAMPLE_AREA));
+        assert!(point(14.3, 15.3).lies_within(&EXAMPLE_AREA));
+        assert!(!point(6.9, 15.0).lies_within(&EXAMPLE_AREA));
+        assert!(!point(15.0, 27.1).lies_within(&EXAMPLE_AREA));
+
+        let area = Area {
+            x: 0.0..=10.0,
+            y: -5.0..=-1.0,
+        };
+        assert!(point(0.0, -5.0).lies_within(&area));
+        // Inside the x range, but outside the y range
+        assert!(!point(5.0, 5.0).lies_within(&area));
+        // Inside the y range, but outside the x range
+        assert!(!point(-3.0, -3.0).lies_within(&area));
+    }
+
+    #[test]
+    fn test_interpolate_to() {
+        let (start, end) = (Point { x: 19.0, y: 13.0 }, Point { x: 14.0, y: 23.0 });
+        assert_eq!(start.interpolate_to(&end, 0.0), start);
+        assert_eq!(start.interpolate_to(&end, 1.0), end);
+        assert_eq!(start.interpolate_to(&end, 0.5), Point { x: 16.5, y: 18.0 });
+        // Fractions outside 0..=1 carry on along the same line
+        assert_eq!(start.interpolate_to(&end, 2.0), Point { x: 9.0, y: 33.0 });
+        assert_eq!(start.interpolate_to(&end, -1.0), Point { x: 24.0, y: 3.0 });
+        assert_eq!(start.interpolate_to(&start, 0.7), start);
+        // Interpolating towards where a hailstone will be reproduces its path
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let hailstone = hailstones[0];
+        let LineRelationship::NonParallelAndIntersecting {
+            point, this_time, ..
+        } = hailstone.relationship_to(&hailstones[1])
+        else {
+            panic!("Expected the first two hailstones' paths to cross")
+        };
+        let one_step_on = Point {
+            x: hailstone.xy_point().x + hailstone.xy_vector().dx as f64,
+            y: hailstone.xy_point().y + hailstone.xy_vector().dy as f64,
+        };
+        assert_near(
+            hailstone.xy_point().interpolate_to(&one_step_on, this_time),
+            point.x,
+            point.y,
+        );
+    }
+
+    #[test]
+    fn test_area_conversions() {
+        let area = Area::square(7, 27);
+        assert!(area.contains(&7, &27));
+        assert!(!area.contains(&6, &27));
+        assert_eq!(Area::<f64>::from(area.clone()), EXAMPLE_AREA);
+        assert_eq!(Area::<i64>::try_from(&EXAMPLE_AREA).unwrap(), area);
+
+        let puzzle_area = Area::square(200_000_000_000_000.0, 400_000_000_000_000.0);
+        assert_eq!(
+            Area::try_from(&puzzle_area).unwrap(),
+            Area::square(200_000_000_000_000, 400_000_000_000_000)
+        );
+        // Only whole numbers that fit in an i64 can be converted
+        for bound in [7.5, -0.1, 1e19, -1e19, f64::INFINITY, f64::NAN] {
+            let area = Area {
+                x: 0.0..=1.0,
+                y: 0.0..=bound,
+            };
+            assert!(Area::<i64>::try_from(&area).is_err(), "{bound}");
+        }
+    }
+
+    #[test]
+    fn test_asymmetric_area() {
+        // Of the two crossings inside the example area, only (14.333, 15.333) has y <= 16
+        let area = Area {
+            x: 7.0..=27.0,
+            y: 7.0..=16.0,
+        };
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        assert_eq!(solve(&hailstones, &area), 1);
+    }
+
+    fn hailstone(input: &str) -> HailstoneTrajectory {
+        input.parse().unwrap()
+    }
+
+    #[test]
+    fn test_vertical_paths() {
+        let up = hailstone("0, 0, 0 @ 0, 1, 0");
+        let down_elsewhere = hailstone("5, 0, 0 @ 0, -1, 0");
+        let down_same_line = hailstone("0, 10, 0 @ 0, -2, 0");
+        let rightwards = hailstone("-3, 5, 0 @ 1, 0, 0");
+
+        assert_eq!(
+            up.relationship_to(&down_elsewhere),
+            LineRelationship::Parallel
+        );
+        assert_eq!(
+            up.relationship_to(&down_same_line),
+            LineRelationship::SameLine
+        );
+        assert_crosses_at(up.relationship_to(&rightwards), 0.0, 5.0);
+        assert_crosses_at(rightwards.relationship_to(&up), 0.0, 5.0);
+        assert_eq!(
+            down_elsewhere.relationship_to(&rightwards),
+            LineRelationship::NonParallelAndIntersectedInThePast(InThePastFor::This)
+        );
+    }
+
+    #[test]
+    fn test_parallel_paths_at_different_speeds() {
+        let slow = hailstone("0, 0, 0 @ 1, 2, 0");
+        let fast = hailstone("1, 0, 0 @ 3, 6, 5");
+        let backwards = hailstone("0, 1, 0 @ -7, -14, 0");
+        let same_line = hailstone("2, 4, 0 @ -5, -10, 3");
+        assert_eq!(slow.relationship_to(&fast), LineRelationship::Parallel);
+        assert_eq!(fast.relationship_to(&backwards), LineRelationship::Parallel);
+        assert_eq!(slow.relationship_to(&same_line), LineRelationship::SameLine);
+        // Only just not parallel, so they cross a long way off
+        let nearly = hailstone("1, 0, 0 @ 300, 601, 0");
+        assert_crosses_at(slow.relationship_to(&nearly), 601.0, 1202.0);
+    }
+
+    #[test]
+    fn test_horizontal_paths() {
+        let right = hailstone("0, 2, 0 @ 1, 0, 0");
+        let left_same_line = hailstone("10, 2, 0 @ -1, 0, 0");
+        let right_elsewhere = hailstone("0, 4, 0 @ 2, 0, 0");
+        let diagonal = hailstone("5, 0, 0 @ -1, 1, 0");
+
+        assert_eq!(
+            right.relationship_to(&left_same_line),
+            LineRelationship::SameLine
+        );
+        assert_eq!(
+            right.relationship_to(&right_elsewhere),
+            LineRelationship::Parallel
+        );
+        assert_crosses_at(right.relationship_to(&diagonal), 3.0, 2.0);
+        assert_crosses_at(right_elsewhere.relationship_to(&diagonal), 1.0, 4.0);
+    }
+
+    #[test]
+    fn test_exact_crossings() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let [a, b, c, d, e] = &hailstones[..] else {
+            panic!("Expected five hailstones")
+        };
+        // (14.333, 15.333) is (43/3, 46/3)
+        let crossing = a.exact_crossing_with(b).unwrap();
+        assert_eq!(crossing.x * 3, crossing.denominator * 43);
+        assert_eq!(crossing.y * 3, crossing.denominator * 46);
+        assert!(crossing.denominator > 0);
+        assert_near(crossing.to_point(), 14.333, 15.333);
+        // In the past for one or both hailstones, or parallel
+        assert_eq!(a.exact_crossing_with(e), None);
+        assert_eq!(c.exact_crossing_with(e), None);
+        assert_eq!(d.exact_crossing_with(e), None);
+        assert_eq!(b.exact_crossing_with(c), None);
+
+        // (7.5, 26.5)
+        let point = RationalPoint {
+            x: 15,
+            y: 53,
+            denominator: 2,
+        };
+        assert!(point.lies_within(&Area::square(7, 27)));
+        assert!(!point.lies_within(&Area::square(8, 27)));
+        assert!(!point.lies_within(&Area::square(7, 26)));
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        assert!(solve_exactly(&hailstones, &Area::square(7.5, 27.0)).is_err());
+    }
+
+    #[test]
+    fn test_crossing_on_the_boundary() {
+        // These paths cross at exactly x = 200000000000000, on the edge of the puzzle's area,
+        // 940127549046 and 103294916953 nanoseconds in. Working in floats puts the crossing
+        // at x = 199999999999999.97 instead, just outside it.
+        let first = hailstone("12914617739846, 481193811378199, 0 @ 199, -271, 0");
+        let second = hailstone("189773803221653, 211648072462454, 0 @ 99, 143, 0");
+        let area = Area::square(200_000_000_000_000.0, 400_000_000_000_000.0);
+
+        let crossing = first.exact_crossing_with(&second).unwrap();
+        assert_eq!(crossing.x, 200_000_000_000_000 * crossing.denominator);
+        assert!(crossing.lies_within(&Area::try_from(&area).unwrap()));
+        assert_eq!(solve_exactly(&[first, second], &area).unwrap(), 1);
+
+        let LineRelationship::NonParallelAndIntersecting { point, .. } =
+            first.relationship_to(&second)
+        else {
+            panic!("Expected the paths to cross in the future")
+        };
+        assert!(point.x < 200_000_000_000_000.0, "{point:?}");
+        assert_eq!(solve(&[first, second], &area), 0);
+    }
+
+    #[test]
+    fn test_parsing() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        assert_eq!(hailstones.len(), 5);
+        assert_eq!(
+            hailstones[4],
+            HailstoneTrajectory {
+                position: Point3 {
+                    x: 20,
+                    y: 19,
+                    z: 15
+                },
+                velocity: Velocity3 {
+                    dx: 1,
+                    dy: -5,
+                    dz: -3
+                }
+            }
+        );
+        assert_eq!(hailstones[4].xy_point(), Point { x: 20.0, y: 19.0 });
+        assert_eq!(hailstones[4].xy_vector(), Vector { dx: 1, dy: -5 });
+
+        let error = parse_input("19, 13, 30 @ -2,  1, -2\n19, 13 @ -2, 1, -2").unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse line 2");
+        let error = parse_input("19, 13, 30 @ -2, 1, -2, 0").unwrap_err();
+        assert_eq!(error.to_string(), "Failed to parse line 1");
+        assert!(format!("{:?}", error.root_cause()).contains("three"));
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let displayed = Vec::from_iter(hailstones.iter().map(ToString::to_string));
+        assert_eq!(
+            displayed,
+            [
+                "19, 13, 30 @ -2, 1, -2",
+                "18, 19, 22 @ -1, -1, -2",
+                "20, 25, 34 @ -2, -2, -4",
+                "12, 31, 28 @ -1, -2, -1",
+                "20, 19, 15 @ 1, -5, -3",
+            ]
+        );
+        let reparsed = parse_input(&displayed.join("\n")).unwrap();
+        assert_eq!(reparsed, hailstones);
+    }
+}
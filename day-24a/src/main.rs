@@ -0,0 +1,189 @@
+use std::cmp::Ordering;
+use std::fs::read_to_string;
+
+use anyhow::{bail, Context, Result};
+use day_24a::{
+    pairwise_relationships, parse_input, solve, solve_exactly, Area, HailstoneTrajectory,
+    LineRelationship,
+};
+
+const DEFAULT_MIN: f64 = 200_000_000_000_000.0;
+const DEFAULT_MAX: f64 = 400_000_000_000_000.0;
+
+/// Which pairs of hailstones `--report` prints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Report {
+    /// Only the pairs that count as crossing inside the area
+    Counted,
+    All,
+}
+
+struct Options {
+    /// Print how every pair of hailstones' paths relate to each other
+    verbose: bool,
+    /// Work out the crossing points with exact fractions rather than floats
+    exact: bool,
+    /// The lower bound of the area on both axes
+    min: f64,
+    /// The upper bound of the area on both axes
+    max: f64,
+    /// Print where and when pairs of hailstones' paths cross
+    report: Option<Report>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            verbose: false,
+            exact: false,
+            min: DEFAULT_MIN,
+            max: DEFAULT_MAX,
+            report: None,
+        }
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Options> {
+    let mut options = Options::default();
+    let mut args = args.peekable();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--min" | "--max" => {
+                let Some(n) = args.next() else {
+                    bail!("Expected a number after `{arg}`")
+                };
+                let bound = n
+                    .parse()
+                    .with_context(|| format!("Invalid bound for `{arg}`: {n}"))?;
+                if arg == "--min" {
+                    options.min = bound
+                } else {
+                    options.max = bound
+                }
+            }
+            "--verbose" => options.verbose = true,
+            "--exact" => options.exact = true,
+            "--report" => {
+                options.report = Some(match args.next_if(|next| next == "all") {
+                    Some(_) => Report::All,
+                    None => Report::Counted,
+                })
+            }
+            _ => bail!("Unrecognised argument {arg:?}"),
+        }
+    }
+    if options.min.partial_cmp(&options.max) != Some(Ordering::Less) {
+        bail!(
+            "Expected the minimum bound to be less than the maximum, got {} and {}",
+            options.min,
+            options.max
+        )
+    }
+    Ok(options)
+}
+
+/// Print how every pair of hailstones' paths relate to each other
+fn describe_pairs(hailstone_trajectories: &[HailstoneTrajectory]) {
+    eprintln!("Found {} hailstones", hailstone_trajectories.len());
+    for (i, first) in hailstone_trajectories.iter().enumerate() {
+        for (j, second) in hailstone_trajectories.iter().enumerate().skip(i + 1) {
+            eprintln!(
+                "Hailstones {i} and {j}: {:?}",
+                first.relationship_to(second)
+            )
+        }
+    }
+}
+
+/// Print where and when the paths of each pair of hailstones cross
+fn report_pairs(hailstone_trajectories: &[HailstoneTrajectory], area: &Area, report: Report) {
+    for (i, j, relationship, counted) in pairwise_relationships(hailstone_trajectories, area) {
+        if report == Report::Counted && !counted {
+            continue;
+        }
+        let LineRelationship::NonParallelAndIntersecting {
+            point,
+            this_time,
+            other_time,
+        } = relationship
+        else {
+            eprintln!("Hailstones {i} and {j}: {relationship:?}");
+            continue;
+        };
+        let whereabouts = if counted { "inside" } else { "outside" };
+        eprintln!(
+            "Hailstones {i} and {j}: cross at ({}, {}), {whereabouts} the area, \
+            after {this_time}ns and {other_time}ns",
+            point.x, point.y
+        )
+    }
+}
+
+fn main() {
+    let options = parse_args(std::env::args().skip(1)).unwrap();
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    let hailstone_trajectories = parse_input(&input).unwrap();
+    if options.verbose {
+        describe_pairs(&hailstone_trajectories)
+    }
+    let area = Area::square(options.min, options.max);
+    if let Some(report) = options.report {
+        report_pairs(&hailstone_trajectories, &area, report)
+    }
+    let crossings = if options.exact {
+        solve_exactly(&hailstone_trajectories, &area).unwrap()
+    } else {
+        solve(&hailstone_trajectories, &area)
+    };
+    println!("{crossings}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{parse_args, Report, DEFAULT_MAX, DEFAULT_MIN};
+
+    #[test]
+    fn test_parse_args() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        let options = parse(&[]).unwrap();
+        assert!(!options.verbose && !options.exact);
+        assert!(parse(&["--verbose"]).unwrap().verbose);
+        let options = parse(&["--exact", "--verbose"]).unwrap();
+        assert!(options.verbose && options.exact);
+        assert!(parse(&["-v"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(parse(&[]).unwrap().report, None);
+        assert_eq!(parse(&["--report"]).unwrap().report, Some(Report::Counted));
+        assert_eq!(
+            parse(&["--report", "all"]).unwrap().report,
+            Some(Report::All)
+        );
+        // `all` is optional, so whatever follows `--report` might be another flag
+        let options = parse(&["--report", "--exact"]).unwrap();
+        assert_eq!(options.report, Some(Report::Counted));
+        assert!(options.exact);
+        assert!(parse(&["--report", "some"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_bounds() {
+        let parse = |args: &[&str]| parse_args(args.iter().map(|arg| arg.to_string()));
+        let options = parse(&[]).unwrap();
+        assert_eq!((options.min, options.max), (DEFAULT_MIN, DEFAULT_MAX));
+        let options = parse(&["--min", "7", "--max", "27"]).unwrap();
+        assert_eq!((options.min, options.max), (7.0, 27.0));
+        assert_eq!(parse(&["--max", "1e15"]).unwrap().max, 1e15);
+
+        assert!(parse(&["--min"]).is_err());
+        assert!(parse(&["--min", "seven"]).is_err());
+        // The minimum must be below the maximum, whichever order they're given in
+        assert!(parse(&["--min", "27", "--max", "7"]).is_err());
+        assert!(parse(&["--max", "7", "--min", "7"]).is_err());
+        assert!(parse(&["--min", "5e14"]).is_err());
+        assert!(parse(&["--min", "NaN"]).is_err());
+    }
+}
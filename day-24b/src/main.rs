@@ -0,0 +1,304 @@
+use std::fs::read_to_string;
+
+use anyhow::{bail, Result};
+use day_24a::{parse_input, HailstoneTrajectory, Point3, Velocity3};
+use num::{BigInt, BigRational, ToPrimitive, Zero};
+
+type Vector3 = [i64; 3];
+
+fn position_components(Point3 { x, y, z }: Point3) -> Vector3 {
+    [x, y, z]
+}
+
+fn velocity_components(Velocity3 { dx, dy, dz }: Velocity3) -> Vector3 {
+    [dx, dy, dz]
+}
+
+fn subtract(a: Vector3, b: Vector3) -> Vector3 {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: Vector3, b: Vector3) -> [i128; 3] {
+    let [a0, a1, a2] = a.map(i128::from);
+    let [b0, b1, b2] = b.map(i128::from);
+    [a1 * b2 - a2 * b1, a2 * b0 - a0 * b2, a0 * b1 - a1 * b0]
+}
+
+/// The matrix `M` such that `M * a = a × w` for any vector `a`
+fn cross_matrix(w: Vector3) -> [[i64; 3]; 3] {
+    [[0, w[2], -w[1]], [-w[2], 0, w[0]], [w[1], -w[0], 0]]
+}
+
+/// Where the rock needs to be thrown from, and how fast, to hit every hailstone
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Throw {
+    position: Point3,
+    velocity: Velocity3,
+}
+
+impl Throw {
+    /// Whether the rock will hit `hailstone` at some time `t >= 0`
+    fn hits(&self, hailstone: &HailstoneTrajectory) -> bool {
+        let gap = subtract(
+            position_components(hailstone.position),
+            position_components(self.position),
+        );
+        let closing_speed = subtract(
+            velocity_components(self.velocity),
+            velocity_components(hailstone.velocity),
+        );
+        // `t` as a fraction, for whichever axes have already pinned it down
+        let mut time: Option<(i128, i128)> = None;
+        for axis in 0..3 {
+            let (gap, speed) = (gap[axis] as i128, closing_speed[axis] as i128);
+            if speed == 0 {
+                if gap != 0 {
+                    return false;
+                }
+                continue;
+            }
+            let (numerator, denominator) = if speed < 0 {
+                (-gap, -speed)
+            } else {
+                (gap, speed)
+            };
+            if numerator < 0 {
+                return false;
+            }
+            match time {
+                None => time = Some((numerator, denominator)),
+                Some((n, d)) if n * denominator != numerator * d => return false,
+                Some(_) => {}
+            }
+        }
+        true
+    }
+}
+
+/// Solve `matrix * x = rhs` exactly by Gaussian elimination,
+/// where each row of `system` is a row of `matrix` followed by the matching entry of `rhs`.
+/// Returns `None` if `matrix` is singular.
+fn solve_linear_system(mut system: Vec<Vec<BigRational>>) -> Option<Vec<BigRational>> {
+    let size = system.len();
+    for column in 0..size {
+        let pivot = (column..size).find(|&row| !system[row][column].is_zero())?;
+        system.swap(column, pivot);
+        for row in 0..size {
+            if row == column || system[row][column].is_zero() {
+                continue;
+            }
+            let factor = &system[row][column] / &system[column][column];
+            let pivot_row = system[column].clone();
+            for (entry, pivot_entry) in system[row].iter_mut().zip(&pivot_row).skip(column) {
+                *entry -= &factor * pivot_entry;
+            }
+        }
+    }
+    Some(Vec::from_iter(
+        (0..size).map(|row| &system[row][size] / &system[row][row]),
+    ))
+}
+
+/// Find the throw that hits hailstones `a`, `b` and `c`, if there's exactly one.
+///
+/// The rock (at `P`, moving at `V`) hits hailstone `i` iff `P - pᵢ` is parallel to
+/// `V - vᵢ`, i.e. `(P - pᵢ) × (V - vᵢ) = 0`. Expanding that gives
+/// `P × V - P × vᵢ - pᵢ × V + pᵢ × vᵢ = 0`, and the only non-linear term, `P × V`,
+/// is the same for every hailstone. Subtracting the equations for two hailstones
+/// `i` and `j` therefore leaves three linear equations:
+/// `P × (vⱼ - vᵢ) + (pⱼ - pᵢ) × V = pⱼ × vⱼ - pᵢ × vᵢ`.
+/// Two pairs of hailstones give six equations for the six unknowns.
+fn throw_hitting(
+    a: &HailstoneTrajectory,
+    b: &HailstoneTrajectory,
+    c: &HailstoneTrajectory,
+) -> Result<Option<Throw>> {
+    let components = |hailstone: &HailstoneTrajectory| {
+        (
+            position_components(hailstone.position),
+            velocity_components(hailstone.velocity),
+        )
+    };
+    let (a_position, a_velocity) = components(a);
+    let mut system = vec![];
+    for other in [b, c] {
+        let (other_position, other_velocity) = components(other);
+        let position_matrix = cross_matrix(subtract(other_velocity, a_velocity));
+        // `d × V = -(V × d)`
+        let velocity_matrix = cross_matrix(subtract(other_position, a_position));
+        let other_cross = cross(other_position, other_velocity);
+        let a_cross = cross(a_position, a_velocity);
+        for row in 0..3 {
+            let mut equation = Vec::from_iter(
+                position_matrix[row]
+                    .iter()
+                    .map(|&coefficient| BigInt::from(coefficient))
+                    .chain(
+                        velocity_matrix[row]
+                            .iter()
+                            .map(|&coefficient| BigInt::from(-coefficient)),
+                    )
+                    .map(BigRational::from_integer),
+            );
+            equation.push(BigRational::from_integer(BigInt::from(
+                other_cross[row] - a_cross[row],
+            )));
+            system.push(equation)
+        }
+    }
+    let Some(solution) = solve_linear_system(system) else {
+        return Ok(None);
+    };
+    let mut unknowns = [0; 6];
+    for (unknown, value) in unknowns.iter_mut().zip(&solution) {
+        if !value.is_integer() {
+            bail!("Expected the rock's position and velocity to be integers, got {value}")
+        }
+        let Some(value) = value.to_integer().to_i64() else {
+            bail!("The rock's position or velocity is too big: {value}")
+        };
+        *unknown = value
+    }
+    let [x, y, z, dx, dy, dz] = unknowns;
+    Ok(Some(Throw {
+        position: Point3 { x, y, z },
+        velocity: Velocity3 { dx, dy, dz },
+    }))
+}
+
+/// Use the first hailstone, plus the first pair of others that pins down the throw
+fn find_throw(hailstones: &[HailstoneTrajectory]) -> Result<Throw> {
+    let Some((a, others)) = hailstones.split_first() else {
+        bail!("Expected at least three hailstones")
+    };
+    for (i, b) in others.iter().enumerate() {
+        for c in &others[i + 1..] {
+            if let Some(throw) = throw_hitting(a, b, c)? {
+                if let Some(missed) = hailstones.iter().find(|h| !throw.hits(h)) {
+                    bail!("The only throw that could work, {throw:?}, misses {missed:?}")
+                }
+                return Ok(throw);
+            }
+        }
+    }
+    bail!("Couldn't find three hailstones that pin down the throw")
+}
+
+fn solve_part_b(input: &str) -> Result<i64> {
+    let hailstones = parse_input(input)?;
+    let throw = find_throw(&hailstones)?;
+    let Point3 { x, y, z } = throw.position;
+    Ok(x + y + z)
+}
+
+fn main() {
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    println!("{}", solve_part_b(&input).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{find_throw, solve_part_b, Throw};
+    use day_24a::{parse_input, HailstoneTrajectory, Point3, Velocity3};
+
+    const EXAMPLE: &str = "\
+19, 13, 30 @ -2,  1, -2
+18, 19, 22 @ -1, -1, -2
+20, 25, 34 @ -2, -2, -4
+12, 31, 28 @ -1, -2, -1
+20, 19, 15 @  1, -5, -3";
+
+    #[test]
+    fn test_example() {
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        let throw = find_throw(&hailstones).unwrap();
+        assert_eq!(
+            throw,
+            Throw {
+                position: Point3 {
+                    x: 24,
+                    y: 13,
+                    z: 10
+                },
+                velocity: Velocity3 {
+                    dx: -3,
+                    dy: 1,
+                    dz: 2
+                }
+            }
+        );
+        assert_eq!(solve_part_b(EXAMPLE).unwrap(), 47);
+    }
+
+    #[test]
+    fn test_hits() {
+        let throw = Throw {
+            position: Point3 {
+                x: 24,
+                y: 13,
+                z: 10,
+            },
+            velocity: Velocity3 {
+                dx: -3,
+                dy: 1,
+                dz: 2,
+            },
+        };
+        let hailstones = parse_input(EXAMPLE).unwrap();
+        assert!(hailstones.iter().all(|hailstone| throw.hits(hailstone)));
+
+        // A hailstone that the rock would only have hit at t = -1
+        let behind: HailstoneTrajectory = "27, 12, 8 @ 0, 0, 0".parse().unwrap();
+        assert!(!throw.hits(&behind));
+        // One that the rock passes at different times on different axes
+        let off_course: HailstoneTrajectory = "21, 14, 13 @ 0, 0, 0".parse().unwrap();
+        assert!(!throw.hits(&off_course));
+    }
+
+    #[test]
+    fn test_puzzle_sized_coordinates() {
+        // Hailstones at the scale of the real input, placed so that the throw
+        // hits each of them about a trillion nanoseconds in
+        let throw = Throw {
+            position: Point3 {
+                x: 287_430_900_174_123,
+                y: 261_786_410_929_342,
+                z: 240_183_237_312_905,
+            },
+            velocity: Velocity3 {
+                dx: -93,
+                dy: 57,
+                dz: 211,
+            },
+        };
+        let velocities = [
+            [-26, 31, 8],
+            [142, -87, -103],
+            [17, 240, -31],
+            [-301, 6, 44],
+        ];
+        let hailstones = Vec::from_iter(velocities.iter().enumerate().map(|(i, &[dx, dy, dz])| {
+            let time = 700_000_000_000 + 123_456_789_011 * i as i64;
+            let (p, v) = (throw.position, throw.velocity);
+            HailstoneTrajectory {
+                position: Point3 {
+                    x: p.x + time * (v.dx - dx),
+                    y: p.y + time * (v.dy - dy),
+                    z: p.z + time * (v.dz - dz),
+                },
+                velocity: Velocity3 { dx, dy, dz },
+            }
+        }));
+        assert_eq!(find_throw(&hailstones).unwrap(), throw);
+    }
+
+    #[test]
+    fn test_no_throw_hits_everything() {
+        let input = format!("{EXAMPLE}\n0, 0, 0 @ 1, 1, 1");
+        let error = solve_part_b(&input).unwrap_err();
+        assert!(error.to_string().contains("misses"), "{error}");
+
+        let error = solve_part_b("19, 13, 30 @ -2,  1, -2").unwrap_err();
+        assert!(error.to_string().contains("pin down"), "{error}");
+    }
+}
@@ -0,0 +1,265 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::read_to_string;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+
+/// The components and the wires between them, as an undirected graph
+#[derive(Debug)]
+struct WiringDiagram {
+    names: Vec<String>,
+    /// Each wire's two ends, by index into `names`
+    wires: Vec<(usize, usize)>,
+    /// For each component, its neighbours and the index of the wire to each of them
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+/// A way of splitting the components into two groups
+#[derive(Debug)]
+struct Cut {
+    /// The wires connecting one group to the other
+    wires: Vec<(usize, usize)>,
+    /// How many components are on the same side as the first one
+    group_size: usize,
+}
+
+impl WiringDiagram {
+    fn node(&mut self, name: &str, indices: &mut HashMap<String, usize>) -> usize {
+        *indices.entry(name.to_string()).or_insert_with(|| {
+            self.names.push(name.to_string());
+            self.adjacency.push(vec![]);
+            self.names.len() - 1
+        })
+    }
+
+    fn connect(&mut self, a: usize, b: usize) {
+        if self.adjacency[a]
+            .iter()
+            .any(|&(neighbour, _)| neighbour == b)
+        {
+            return;
+        }
+        let wire = self.wires.len();
+        self.wires.push((a, b));
+        self.adjacency[a].push((b, wire));
+        self.adjacency[b].push((a, wire));
+    }
+
+    /// Send up to `limit` units of flow from `source` to `sink`, treating each wire as a
+    /// pipe of capacity 1 in either direction. Returns how much flow got through; if that's
+    /// less than `limit`, it's the size of the smallest cut separating the two, and the
+    /// components still reachable from `source` are the source's side of that cut.
+    fn max_flow(&self, source: usize, sink: usize, limit: usize) -> (usize, Vec<bool>) {
+        // The net flow along each wire, from its first end to its second
+        let mut flow = vec![0i8; self.wires.len()];
+        let mut total = 0;
+        loop {
+            // Breadth-first search for a path with spare capacity (Edmonds-Karp)
+            let mut came_from = vec![None; self.names.len()];
+            let mut reached = vec![false; self.names.len()];
+            reached[source] = true;
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                if node == sink {
+                    break;
+                }
+                for &(neighbour, wire) in &self.adjacency[node] {
+                    let forwards = self.wires[wire].0 == node;
+                    let spare = if forwards {
+                        1 - flow[wire]
+                    } else {
+                        1 + flow[wire]
+                    };
+                    if spare > 0 && !reached[neighbour] {
+                        reached[neighbour] = true;
+                        came_from[neighbour] = Some((node, wire));
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+            if !reached[sink] || total == limit {
+                return (total, reached);
+            }
+            let mut node = sink;
+            while let Some((previous, wire)) = came_from[node] {
+                flow[wire] += if self.wires[wire].0 == previous {
+                    1
+                } else {
+                    -1
+                };
+                node = previous;
+            }
+            total += 1;
+        }
+    }
+
+    /// Find a smallest cut by computing the maximum flow from the first component to every
+    /// other one. Any cut puts some component on the opposite side from the first, so the
+    /// smallest of those flows is the size of the smallest cut overall.
+    /// Returns `None` if every cut is more than `limit` wires, which saves pushing
+    /// any more flow than that between each pair.
+    fn minimum_cut(&self, limit: usize) -> Result<Option<Cut>> {
+        if self.names.len() < 2 {
+            bail!(
+                "Expected at least two components, found {}",
+                self.names.len()
+            )
+        }
+        let mut smallest: Option<(usize, Vec<bool>)> = None;
+        for sink in 1..self.names.len() {
+            let cap = smallest.as_ref().map_or(limit + 1, |(size, _)| *size);
+            let (size, source_side) = self.max_flow(0, sink, cap);
+            if smallest.as_ref().is_none_or(|(best, _)| size < *best) {
+                smallest = Some((size, source_side));
+            }
+        }
+        let (size, source_side) = smallest.unwrap();
+        if size > limit {
+            return Ok(None);
+        }
+        let wires = Vec::from_iter(
+            self.wires
+                .iter()
+                .copied()
+                .filter(|&(a, b)| source_side[a] != source_side[b]),
+        );
+        let group_size = source_side.iter().filter(|&&side| side).count();
+        Ok(Some(Cut { wires, group_size }))
+    }
+}
+
+impl FromStr for WiringDiagram {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut diagram = WiringDiagram {
+            names: vec![],
+            wires: vec![],
+            adjacency: vec![],
+        };
+        let mut indices = HashMap::new();
+        for (i, line) in s.lines().enumerate() {
+            let Some((name, neighbours)) = line.split_once(':') else {
+                bail!(
+                    "Expected line {} to look like `name: other names`, got {line:?}",
+                    i + 1
+                )
+            };
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                bail!("Invalid component name {name:?} on line {}", i + 1)
+            }
+            let node = diagram.node(name, &mut indices);
+            for neighbour in neighbours.split_whitespace() {
+                if neighbour == name {
+                    bail!("Component {name:?} is wired to itself on line {}", i + 1)
+                }
+                let neighbour = diagram.node(neighbour, &mut indices);
+                diagram.connect(node, neighbour);
+            }
+        }
+        Ok(diagram)
+    }
+}
+
+fn solve(input: &str) -> Result<usize> {
+    let diagram = WiringDiagram::from_str(input).context("Failed to parse the wiring diagram")?;
+    let Some(cut) = diagram.minimum_cut(3)? else {
+        bail!("Expected the smallest cut to be exactly three wires, but it's more than three")
+    };
+    if cut.wires.len() != 3 {
+        bail!(
+            "Expected the smallest cut to be exactly three wires, but it's {}",
+            cut.wires.len()
+        )
+    }
+    Ok(cut.group_size * (diagram.names.len() - cut.group_size))
+}
+
+fn main() {
+    let input = read_to_string("input.txt").expect("Expected 'input.txt' to exist as a file!");
+    println!("{}", solve(&input).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use crate::{solve, WiringDiagram};
+
+    const EXAMPLE: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_example() {
+        assert_eq!(solve(EXAMPLE).unwrap(), 54)
+    }
+
+    #[test]
+    fn test_example_cut() {
+        let diagram: WiringDiagram = EXAMPLE.parse().unwrap();
+        assert_eq!(diagram.names.len(), 15);
+        assert_eq!(diagram.wires.len(), 33);
+        let cut = diagram.minimum_cut(3).unwrap().unwrap();
+        let cut_wires = HashSet::<[&str; 2]>::from_iter(cut.wires.iter().map(|&(a, b)| {
+            let mut ends = [diagram.names[a].as_str(), diagram.names[b].as_str()];
+            ends.sort();
+            ends
+        }));
+        assert_eq!(
+            cut_wires,
+            HashSet::from([["hfx", "pzl"], ["bvb", "cmg"], ["jqt", "nvd"]])
+        );
+        // `jqt` is in the group of 6, so the first component's side could be either
+        assert!([6, 9].contains(&cut.group_size), "{cut:?}");
+    }
+
+    #[test]
+    fn test_duplicate_wires() {
+        // Listing a wire from both ends doesn't make it two wires
+        let input = format!("{EXAMPLE}\nhfx: pzl");
+        let diagram: WiringDiagram = input.parse().unwrap();
+        assert_eq!(diagram.wires.len(), 33);
+        assert_eq!(solve(&input).unwrap(), 54)
+    }
+
+    #[test]
+    fn test_wrong_sized_cut() {
+        // Two triangles joined by a single wire
+        let error = solve("a: b c\nb: c\nc: d\nd: e f\ne: f").unwrap_err();
+        assert!(error.to_string().contains("but it's 1"), "{error}");
+        // Two groups that aren't connected at all
+        let error = solve("a: b c\nb: c\nd: e f\ne: f").unwrap_err();
+        assert!(error.to_string().contains("but it's 0"), "{error}");
+        // Every component is connected to every other one: the smallest cut is 4
+        let error = solve("a: b c d e\nb: c d e\nc: d e\nd: e").unwrap_err();
+        assert!(error.to_string().contains("more than three"), "{error}");
+        // A fourth wire between the example's two groups
+        let error = solve(&format!("{EXAMPLE}\ncmg: rhn")).unwrap_err();
+        assert!(error.to_string().contains("more than three"), "{error}");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = solve("jqt rhn xhk").unwrap_err();
+        assert!(format!("{error:#}").contains("line 1"), "{error:#}");
+        let error = solve("jqt: rhn\n: xhk").unwrap_err();
+        assert!(format!("{error:#}").contains("line 2"), "{error:#}");
+        let error = solve("jqt: jqt").unwrap_err();
+        assert!(format!("{error:#}").contains("itself"), "{error:#}");
+        let error = solve("").unwrap_err();
+        assert!(error.to_string().contains("at least two"), "{error}");
+    }
+}